@@ -1,8 +1,24 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
-use std::sync::{Arc, RwLock};
-use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use hashers::fnv::FNV1aHasher64;
+use hashers::jenkins::spooky_hash::SpookyHasher;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "tracing")]
+use tracing::error;
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! error {
+    ($($arg:tt)*) => {{
+        let _ = format_args!($($arg)*);
+    }};
+}
 
 use crate::dto::GrowthBookResponse;
 
@@ -18,6 +34,25 @@ pub trait FeatureCache: Send + Sync {
         key: &str,
         value: GrowthBookResponse,
     ) -> BoxFuture<'_, ()>;
+
+    /// The entry even if it has expired. Used as a fallback when the payload
+    /// can't be fetched and nothing else is being served; caches that drop
+    /// expired entries can keep the default.
+    fn get_stale(
+        &self,
+        key: &str,
+    ) -> BoxFuture<'_, Option<GrowthBookResponse>> {
+        self.get(key)
+    }
+
+    /// Drops the entry, e.g. to clear an attribute set's remote evaluation
+    /// payload. The client never calls it: entries expire with the cache.
+    fn remove(
+        &self,
+        _key: &str,
+    ) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
 }
 
 #[derive(Clone)]
@@ -63,7 +98,245 @@ impl FeatureCache for InMemoryCache {
         let ttl = self.ttl;
         Box::pin(async move {
             let mut cache = cache.write().unwrap();
-            cache.insert(key, (value, Instant::now() + ttl));
+            let now = Instant::now();
+            cache.retain(|_, (_, expires_at)| now < *expires_at);
+            cache.insert(key, (value, now + ttl));
+        })
+    }
+
+    fn remove(
+        &self,
+        key: &str,
+    ) -> BoxFuture<'_, ()> {
+        let key = key.to_string();
+        let cache = self.cache.clone();
+        Box::pin(async move {
+            cache.write().unwrap().remove(&key);
+        })
+    }
+}
+
+/// Persists payloads as JSON files in a directory, one per cache key, along
+/// with when they were fetched. Entries survive restarts, so a process can
+/// start from the last payload when the API is unreachable. Writes go to a
+/// temporary file that is renamed into place, so readers never see a
+/// partial payload. Expired entries are kept as the stale fallback;
+/// `prune` deletes the old ones nobody is using.
+#[derive(Clone, Debug)]
+pub struct FileCache {
+    directory: PathBuf,
+    ttl: Duration,
+    // Files this cache (or a clone) has written or read; `prune` keeps them.
+    used: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileCacheEntry {
+    /// `key_hash` of the key. Keys can embed user attributes, so they are
+    /// not written to disk.
+    #[serde(default)]
+    key_hash: String,
+    /// Milliseconds since the Unix epoch.
+    fetched_at: u64,
+    payload: GrowthBookResponse,
+}
+
+// Distinguishes temporary files written concurrently by one process.
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A sibling of `path` to write to before renaming it over `path`, unique
+/// across processes and concurrent writers.
+pub(crate) fn temp_path(path: &Path) -> PathBuf {
+    path.with_extension(format!("{}.{}.tmp", std::process::id(), TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)))
+}
+
+impl FileCache {
+    /// Entries are stored in `directory`, which is created on first write.
+    pub fn new(
+        directory: impl Into<PathBuf>,
+        ttl: Duration,
+    ) -> Self {
+        Self {
+            directory: directory.into(),
+            ttl,
+            used: Arc::default(),
+        }
+    }
+
+    /// Deletes the entries in the directory older than `max_age`, e.g. those
+    /// of remote evaluation attributes no longer in use, and returns how
+    /// many. Entries this cache has written or read are never deleted, since
+    /// they are the fallback when the API is unreachable.
+    pub async fn prune(
+        &self,
+        max_age: Duration,
+    ) -> usize {
+        let Ok(mut entries) = tokio::fs::read_dir(&self.directory).await else {
+            return 0;
+        };
+        let mut pruned = 0;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if !name.starts_with("growthbook-") || !name.ends_with(".json") || self.is_used(&path) {
+                continue;
+            }
+            // Files are renamed into place when written, so their
+            // modification time is when they were fetched.
+            let old = entry
+                .metadata()
+                .await
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age >= max_age);
+            if !old {
+                continue;
+            }
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => pruned += 1,
+                Err(e) => error!("[growthbook-sdk] Failed to delete old cache file {:?}: {:?}", path, e),
+            }
+        }
+        pruned
+    }
+
+    fn used(&self) -> MutexGuard<'_, HashSet<PathBuf>> {
+        self.used.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn is_used(
+        &self,
+        path: &Path,
+    ) -> bool {
+        self.used().contains(path)
+    }
+
+    // Keys can be long or contain any character (remote evaluation keys
+    // embed the attributes), so files are named after a hash of the key.
+    fn path(
+        &self,
+        key: &str,
+    ) -> PathBuf {
+        let mut hasher = FNV1aHasher64::default();
+        hasher.write(key.as_bytes());
+        self.directory.join(format!("growthbook-{:016x}.json", hasher.finish()))
+    }
+
+    // Stored in the entry to tell keys whose file names collide apart; it is
+    // a different hash from the file name's, so they don't collide together.
+    fn key_hash(key: &str) -> String {
+        let mut hasher = SpookyHasher::new(0, 0);
+        hasher.write(key.as_bytes());
+        let (high, low) = hasher.finish128();
+        format!("{high:016x}{low:016x}")
+    }
+
+    async fn read(
+        &self,
+        key: &str,
+    ) -> Option<FileCacheEntry> {
+        let path = self.path(key);
+        let contents = tokio::fs::read(&path).await.ok()?;
+        match serde_json::from_slice::<FileCacheEntry>(&contents) {
+            // A hash collision reads another key's entry.
+            Ok(entry) if entry.key_hash == Self::key_hash(key) => {
+                self.used().insert(path);
+                Some(entry)
+            },
+            Ok(_) => None,
+            Err(e) => {
+                error!("[growthbook-sdk] Ignoring unreadable cache file for '{}': {:?}", key, e);
+                None
+            },
+        }
+    }
+
+    async fn write(
+        &self,
+        path: &Path,
+        contents: &[u8],
+    ) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(&self.directory).await?;
+        let temp_path = temp_path(path);
+        if let Err(e) = tokio::fs::write(&temp_path, contents).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(e);
+        }
+        tokio::fs::rename(&temp_path, path).await
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|it| it.as_millis() as u64).unwrap_or_default()
+}
+
+impl FeatureCache for FileCache {
+    fn get(
+        &self,
+        key: &str,
+    ) -> BoxFuture<'_, Option<GrowthBookResponse>> {
+        let key = key.to_string();
+        Box::pin(async move {
+            let entry = self.read(&key).await?;
+            let age = Duration::from_millis(now_millis().saturating_sub(entry.fetched_at));
+            (age < self.ttl).then_some(entry.payload)
+        })
+    }
+
+    fn set(
+        &self,
+        key: &str,
+        value: GrowthBookResponse,
+    ) -> BoxFuture<'_, ()> {
+        let key = key.to_string();
+        Box::pin(async move {
+            let path = self.path(&key);
+            let entry = FileCacheEntry {
+                key_hash: Self::key_hash(&key),
+                fetched_at: now_millis(),
+                payload: value,
+            };
+            let result = match serde_json::to_vec(&entry) {
+                Ok(contents) => self.write(&path, &contents).await,
+                Err(e) => Err(e.into()),
+            };
+            match result {
+                Ok(()) => {
+                    self.used().insert(path);
+                },
+                Err(e) => error!("[growthbook-sdk] Failed to write cache file {:?}: {:?}", path, e),
+            }
+        })
+    }
+
+    fn get_stale(
+        &self,
+        key: &str,
+    ) -> BoxFuture<'_, Option<GrowthBookResponse>> {
+        let key = key.to_string();
+        Box::pin(async move { self.read(&key).await.map(|entry| entry.payload) })
+    }
+
+    fn remove(
+        &self,
+        key: &str,
+    ) -> BoxFuture<'_, ()> {
+        let key = key.to_string();
+        Box::pin(async move {
+            // Only this key's entry; a colliding key's file is left alone.
+            if self.read(&key).await.is_none() {
+                return;
+            }
+            let path = self.path(&key);
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => {
+                    self.used().remove(&path);
+                },
+                Err(e) => error!("[growthbook-sdk] Failed to delete cache file {:?}: {:?}", path, e),
+            }
         })
     }
 }
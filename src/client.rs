@@ -1,44 +1,135 @@
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::sync::{Arc, RwLock};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Duration;
 
-use tokio::time::sleep;
+use arc_swap::ArcSwap;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::time::{sleep, timeout};
 #[cfg(feature = "tracing")]
 use tracing::error;
 
 #[cfg(not(feature = "tracing"))]
 macro_rules! error {
-    ($($arg:tt)*) => {
+    ($($arg:tt)*) => {{
         let _ = format_args!($($arg)*);
-    };
+    }};
 }
 
 use crate::cache::{FeatureCache, InMemoryCache};
 use crate::condition::eval_context::{saved_groups_from_value, SavedGroups};
-use crate::dto::GrowthBookResponse;
+use crate::dto::{GrowthBookResponse, RemoteEvalRequest};
 use crate::env::Environment;
 use crate::error::GrowthbookError;
-use crate::gateway::GrowthbookGateway;
-use crate::growthbook::GrowthBook;
-use crate::model_public::{ExperimentResult, FeatureResult, GrowthBookAttribute};
-use crate::sticky_bucket::StickyBucketService;
+use crate::gateway::{CacheValidators, FeaturesFetch, GrowthbookGateway};
+use crate::growthbook::{FeatureSnapshot, GrowthBook, PreparedUser};
+use crate::model_public::{Experiment, ExperimentResult, FeatureExplanation, FeatureResult, GrowthBookAttribute, RedirectResult};
+use crate::sticky_bucket::{AsyncStickyBucketService, PrefetchedStickyBuckets, StickyBucketService};
+use crate::streaming::{Backoff, SseParser, STREAM_IDLE_TIMEOUT};
+use crate::tracking::{tracking_key, TrackedExperiments, DEFAULT_TRACKED_EXPERIMENTS_CAPACITY};
 
 pub type OnFeatureUsageCallback = Arc<dyn Fn(String, FeatureResult) + Send + Sync>;
 pub type OnExperimentViewedCallback = Arc<dyn Fn(ExperimentResult) + Send + Sync>;
+pub type OnFeatureValueMismatchCallback = Arc<dyn Fn(String, FeatureResult, GrowthbookError) + Send + Sync>;
 pub type OnRefreshCallback = Arc<dyn Fn() + Send + Sync>; // Keeping it simple for now, maybe pass features later if needed
 
 #[derive(Clone)]
 pub struct GrowthBookClient {
-    pub gb: Arc<RwLock<GrowthBook>>,
+    snapshots: Arc<SnapshotStore>,
     pub cache: Option<Arc<dyn FeatureCache>>,
     gateway: Option<Arc<GrowthbookGateway>>,
     auto_refresh: bool,
     refresh_interval: Duration,
     pub on_feature_usage: Option<OnFeatureUsageCallback>,
     pub on_experiment_viewed: Option<OnExperimentViewedCallback>,
+    pub on_feature_value_mismatch: Option<OnFeatureValueMismatchCallback>,
     pub on_refresh: Vec<OnRefreshCallback>,
     pub decryption_key: Option<String>,
+    tracked_experiments: Option<Arc<TrackedExperiments>>,
+    remote_eval: bool,
+    streaming: bool,
+    // Validators and payload of the last conditional fetch, reused on 304.
+    last_fetched: Arc<Mutex<Option<(CacheValidators, GrowthBookResponse)>>>,
+    stale_while_revalidate: bool,
+    payload_state: Arc<PayloadState>,
+    async_sticky_bucket_service: Option<Arc<dyn AsyncStickyBucketService>>,
+    // Set once a synchronous getter has logged that it skipped sticky buckets.
+    skipped_sticky_buckets_reported: Arc<AtomicBool>,
+    // Hash of the condition errors last logged, so a refresh that reloads
+    // the same payload doesn't log them again.
+    reported_condition_errors: Arc<AtomicU64>,
+}
+
+// The current snapshot. Readers load it without locking, so they never wait
+// on a refresh; writers are serialized and swap in a modified copy.
+#[derive(Debug)]
+struct SnapshotStore {
+    current: ArcSwap<FeatureSnapshot>,
+    writer: Mutex<()>,
+}
+
+impl SnapshotStore {
+    fn new(snapshot: GrowthBook) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(FeatureSnapshot::new(snapshot)),
+            writer: Mutex::new(()),
+        }
+    }
+
+    fn load(&self) -> Arc<FeatureSnapshot> {
+        self.current.load_full()
+    }
+
+    fn update(
+        &self,
+        update: impl FnOnce(&mut GrowthBook),
+    ) {
+        // The guard protects no data, so a panicked writer leaves nothing
+        // inconsistent behind.
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut next = GrowthBook::clone(&self.current.load());
+        update(&mut next);
+        self.current.store(Arc::new(FeatureSnapshot::new(next)));
+    }
+}
+
+// Which payload is being served, and whether it is past its TTL.
+#[derive(Debug, Default)]
+struct PayloadState {
+    cache_key: Mutex<Option<String>>,
+    stale: AtomicBool,
+    revalidating: AtomicBool,
+}
+
+impl PayloadState {
+    fn is_serving(
+        &self,
+        cache_key: &str,
+    ) -> bool {
+        self.cache_key.lock().is_ok_and(|serving| serving.as_deref() == Some(cache_key))
+    }
+
+    fn mark_fresh(
+        &self,
+        cache_key: &str,
+    ) {
+        if let Ok(mut serving) = self.cache_key.lock() {
+            *serving = Some(cache_key.to_string());
+        }
+        self.stale.store(false, Ordering::SeqCst);
+    }
+
+    fn mark_stale(
+        &self,
+        cache_key: &str,
+    ) {
+        self.mark_fresh(cache_key);
+        self.stale.store(true, Ordering::SeqCst);
+    }
 }
 
 impl Debug for GrowthBookClient {
@@ -47,13 +138,20 @@ impl Debug for GrowthBookClient {
         f: &mut std::fmt::Formatter<'_>,
     ) -> std::fmt::Result {
         f.debug_struct("GrowthBookClient")
-            .field("gb", &self.gb)
+            .field("snapshot", &self.snapshot())
             .field("auto_refresh", &self.auto_refresh)
             .field("refresh_interval", &self.refresh_interval)
             .field("on_feature_usage", &self.on_feature_usage.is_some())
             .field("on_experiment_viewed", &self.on_experiment_viewed.is_some())
+            .field("on_feature_value_mismatch", &self.on_feature_value_mismatch.is_some())
             .field("on_refresh", &self.on_refresh.len())
             .field("decryption_key", &self.decryption_key.is_some())
+            .field("tracked_experiments", &self.tracked_experiments.is_some())
+            .field("remote_eval", &self.remote_eval)
+            .field("streaming", &self.streaming)
+            .field("stale_while_revalidate", &self.stale_while_revalidate)
+            .field("payload_state", &self.payload_state)
+            .field("async_sticky_bucket_service", &self.async_sticky_bucket_service)
             .finish()
     }
 }
@@ -68,11 +166,23 @@ pub struct GrowthBookClientBuilder {
     attributes: Option<HashMap<String, GrowthBookAttribute>>,
     on_feature_usage: Option<OnFeatureUsageCallback>,
     on_experiment_viewed: Option<OnExperimentViewedCallback>,
+    on_feature_value_mismatch: Option<OnFeatureValueMismatchCallback>,
     on_refresh: Vec<OnRefreshCallback>,
     features: Option<HashMap<String, crate::dto::GrowthBookFeature>>,
     decryption_key: Option<String>,
     sticky_bucket_service: Option<Arc<dyn StickyBucketService>>,
+    async_sticky_bucket_service: Option<Arc<dyn AsyncStickyBucketService>>,
     saved_groups: SavedGroups,
+    query_string_overrides: bool,
+    forced_features: HashMap<String, Value>,
+    forced_variations: HashMap<String, i64>,
+    enabled: bool,
+    qa_mode: bool,
+    deduplicate_experiment_viewed: bool,
+    tracked_experiments_capacity: usize,
+    remote_eval: bool,
+    streaming: bool,
+    stale_while_revalidate: bool,
 }
 
 impl Default for GrowthBookClientBuilder {
@@ -93,11 +203,23 @@ impl GrowthBookClientBuilder {
             attributes: None,
             on_feature_usage: None,
             on_experiment_viewed: None,
+            on_feature_value_mismatch: None,
             on_refresh: Vec::new(),
             features: None,
             decryption_key: None,
             sticky_bucket_service: None,
+            async_sticky_bucket_service: None,
             saved_groups: SavedGroups::new(),
+            query_string_overrides: false,
+            forced_features: HashMap::new(),
+            forced_variations: HashMap::new(),
+            enabled: true,
+            qa_mode: false,
+            deduplicate_experiment_viewed: true,
+            tracked_experiments_capacity: DEFAULT_TRACKED_EXPERIMENTS_CAPACITY,
+            remote_eval: false,
+            streaming: false,
+            stale_while_revalidate: false,
         }
     }
 
@@ -173,6 +295,17 @@ impl GrowthBookClientBuilder {
         self
     }
 
+    /// Called when a typed getter (`get_feature_value`, `get_bool`, ...)
+    /// finds a value that isn't of the requested type and falls back to the
+    /// default. Without it the mismatch is logged.
+    pub fn on_feature_value_mismatch(
+        mut self,
+        callback: Box<dyn Fn(String, FeatureResult, GrowthbookError) + Send + Sync>,
+    ) -> Self {
+        self.on_feature_value_mismatch = Some(Arc::from(callback));
+        self
+    }
+
     pub fn add_on_refresh(
         mut self,
         callback: Box<dyn Fn() + Send + Sync>,
@@ -225,6 +358,134 @@ impl GrowthBookClientBuilder {
         self
     }
 
+    /// Sticky bucket storage with async I/O, used by `feature_result_async`
+    /// and `evaluate_all_async`; the synchronous getters evaluate without
+    /// sticky buckets, logging an error the first time. Can't be combined with
+    /// `sticky_bucket_service`.
+    pub fn async_sticky_bucket_service(
+        mut self,
+        service: Arc<dyn AsyncStickyBucketService>,
+    ) -> Self {
+        self.async_sticky_bucket_service = Some(service);
+        self
+    }
+
+    /// Whether `?{experiment_key}={variation}` in the `url` attribute forces
+    /// an experiment variation (JS `getQueryStringOverride`). Disabled by
+    /// default, since it lets visitors pick their own variation; enable it
+    /// for QA environments.
+    pub fn query_string_overrides(
+        mut self,
+        enabled: bool,
+    ) -> Self {
+        self.query_string_overrides = enabled;
+        self
+    }
+
+    /// Pin features to fixed values, bypassing their rules. Evaluations of
+    /// these features report the `override` source.
+    pub fn forced_features(
+        mut self,
+        forced_features: HashMap<String, Value>,
+    ) -> Self {
+        self.forced_features = forced_features;
+        self
+    }
+
+    /// Force experiment variations by experiment key. They take precedence
+    /// over forced variations in the payload and survive refreshes.
+    pub fn forced_variations(
+        mut self,
+        forced_variations: HashMap<String, i64>,
+    ) -> Self {
+        self.forced_variations = forced_variations;
+        self
+    }
+
+    /// Global kill switch for experiments (JS `enabled`). When false, every
+    /// experiment rule is skipped and inline experiments return the control
+    /// with `in_experiment = false`, so nothing is tracked. Forced features
+    /// and force/rollout rules still apply.
+    pub fn enabled(
+        mut self,
+        enabled: bool,
+    ) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// QA mode (JS `qaMode`): users are only put in experiments through
+    /// forced variations, never by random assignment.
+    pub fn qa_mode(
+        mut self,
+        qa_mode: bool,
+    ) -> Self {
+        self.qa_mode = qa_mode;
+        self
+    }
+
+    /// Whether `on_experiment_viewed` fires only once per hash attribute,
+    /// hash value, experiment key and variation (the JS behaviour). Enabled by
+    /// default; disable it to receive every exposure.
+    pub fn deduplicate_experiment_viewed(
+        mut self,
+        enabled: bool,
+    ) -> Self {
+        self.deduplicate_experiment_viewed = enabled;
+        self
+    }
+
+    /// How many exposures are remembered for de-duplication before the least
+    /// recently seen ones are forgotten (and may be tracked again). Defaults to
+    /// 1000; 0 remembers nothing.
+    pub fn tracked_experiments_capacity(
+        mut self,
+        capacity: usize,
+    ) -> Self {
+        self.tracked_experiments_capacity = capacity;
+        self
+    }
+
+    /// Remote evaluation: `refresh` posts the client's attributes and forced
+    /// variations to `/api/eval/{clientKey}` and receives features already
+    /// evaluated for them, so targeting rules and saved groups stay on the
+    /// server. Payloads are cached per attribute set, and
+    /// `GrowthBookClient::set_attributes` re-fetches when the attributes
+    /// change. Requires `api_url` and `client_key`.
+    pub fn remote_eval(
+        mut self,
+        enabled: bool,
+    ) -> Self {
+        self.remote_eval = enabled;
+        self
+    }
+
+    /// Streaming updates: subscribe to the server-sent events endpoint
+    /// (`/sub/{clientKey}`, e.g. GrowthBook Proxy) and apply each pushed
+    /// payload as it arrives, instead of polling every `refresh_interval`.
+    /// While the stream is down the client polls and reconnects with
+    /// exponential backoff capped at `refresh_interval`. Requires `api_url`
+    /// and `client_key`; not available with `remote_eval`.
+    pub fn streaming(
+        mut self,
+        enabled: bool,
+    ) -> Self {
+        self.streaming = enabled;
+        self
+    }
+
+    /// Once a payload is loaded, a refresh that finds it expired returns
+    /// immediately and re-fetches in the background; the expired payload is
+    /// served (see `GrowthBookClient::is_stale`) until that succeeds, however
+    /// long the API is down. Off by default: `refresh` waits for the network.
+    pub fn stale_while_revalidate(
+        mut self,
+        enabled: bool,
+    ) -> Self {
+        self.stale_while_revalidate = enabled;
+        self
+    }
+
     pub async fn build(self) -> Result<GrowthBookClient, GrowthbookError> {
         // Gateway is optional now (for offline mode)
         let gateway = if let (Some(api_url), Some(client_key)) = (&self.api_url, &self.client_key) {
@@ -241,6 +502,27 @@ impl GrowthBookClientBuilder {
             ));
         }
 
+        if self.remote_eval && gateway.is_none() {
+            return Err(GrowthbookError::new(
+                crate::error::GrowthbookErrorCode::ConfigError,
+                "Remote evaluation requires 'api_url' + 'client_key'",
+            ));
+        }
+
+        if self.sticky_bucket_service.is_some() && self.async_sticky_bucket_service.is_some() {
+            return Err(GrowthbookError::new(
+                crate::error::GrowthbookErrorCode::ConfigError,
+                "Configure either 'sticky_bucket_service' or 'async_sticky_bucket_service', not both",
+            ));
+        }
+
+        if self.streaming && (gateway.is_none() || self.remote_eval) {
+            return Err(GrowthbookError::new(
+                crate::error::GrowthbookErrorCode::ConfigError,
+                "Streaming requires 'api_url' + 'client_key' and is not available with remote evaluation",
+            ));
+        }
+
         let refresh_interval = self.refresh_interval.unwrap_or_else(|| {
             let seconds = Environment::u64_or_default("GB_UPDATE_INTERVAL", 60);
             Duration::from_secs(seconds)
@@ -254,12 +536,18 @@ impl GrowthBookClientBuilder {
         });
 
         let client = GrowthBookClient {
-            gb: Arc::new(RwLock::new(GrowthBook {
+            snapshots: Arc::new(SnapshotStore::new(GrowthBook {
                 forced_variations: None,
+                local_forced_variations: self.forced_variations,
+                forced_features: Arc::new(self.forced_features),
                 features: self.features.clone().unwrap_or_default(), // Use cloned features if present
                 attributes: self.attributes,
                 sticky_bucket_service: self.sticky_bucket_service,
                 saved_groups: self.saved_groups,
+                experiments: Vec::new(),
+                query_string_overrides: self.query_string_overrides,
+                enabled: self.enabled,
+                qa_mode: self.qa_mode,
             })),
             cache: Some(cache),
             gateway: gateway_arc,
@@ -267,17 +555,31 @@ impl GrowthBookClientBuilder {
             refresh_interval,
             on_feature_usage: self.on_feature_usage,
             on_experiment_viewed: self.on_experiment_viewed,
+            on_feature_value_mismatch: self.on_feature_value_mismatch,
             on_refresh: self.on_refresh,
             decryption_key: self.decryption_key,
+            tracked_experiments: self.deduplicate_experiment_viewed.then(|| Arc::new(TrackedExperiments::new(self.tracked_experiments_capacity))),
+            remote_eval: self.remote_eval,
+            streaming: self.streaming,
+            last_fetched: Arc::new(Mutex::new(None)),
+            stale_while_revalidate: self.stale_while_revalidate,
+            payload_state: Arc::new(PayloadState::default()),
+            async_sticky_bucket_service: self.async_sticky_bucket_service,
+            skipped_sticky_buckets_reported: Arc::new(AtomicBool::new(false)),
+            reported_condition_errors: Arc::new(AtomicU64::new(0)),
         };
 
         // Initial load: Only when there are no manual features
         // If we have manual features, we assume they are the source of truth for start.
         if self.features.is_none() {
             client.refresh().await;
+        } else {
+            client.report_condition_errors();
         }
 
-        if client.auto_refresh && client.gateway.is_some() {
+        if client.streaming {
+            client.start_streaming();
+        } else if client.auto_refresh && client.gateway.is_some() {
             client.start_auto_refresh();
         }
 
@@ -286,31 +588,115 @@ impl GrowthBookClientBuilder {
 }
 
 impl GrowthBookClient {
+    /// Loads the payload from the cache or the network. With
+    /// [`GrowthBookClientBuilder::stale_while_revalidate`], once a payload has
+    /// been loaded an expired one keeps being served while it is re-fetched
+    /// in the background, so this returns without waiting on the network.
     pub async fn refresh(&self) {
-        if let Some(gateway) = &self.gateway {
-            let cache_key = "features";
-
-            // Try cache first
-            if let Some(cache) = &self.cache {
-                if let Some(response) = cache.get(cache_key).await {
-                    self.update_gb(response);
-                    return;
-                }
+        if self.gateway.is_none() {
+            return;
+        }
+        let remote_eval_request = self.remote_eval.then(|| self.remote_eval_request());
+        let cache_key = remote_eval_request.as_ref().map_or(String::from("features"), RemoteEvalRequest::cache_key);
+
+        // Try cache first
+        if let Some(cache) = &self.cache {
+            if let Some(response) = cache.get(&cache_key).await {
+                self.update_gb(response);
+                self.payload_state.mark_fresh(&cache_key);
+                return;
             }
+        }
 
-            // Fetch from network
-            match gateway.get_features(None).await {
-                Ok(response) => {
-                    // Update cache
-                    if let Some(cache) = &self.cache {
-                        cache.set(cache_key, response.clone()).await;
-                    }
-                    self.update_gb(response);
-                },
-                Err(e) => {
-                    error!("[growthbook-sdk] Failed to fetch features: {:?}", e);
-                },
+        // The payload being served for this key has expired: keep serving it
+        // and revalidate in the background, one fetch at a time.
+        if self.stale_while_revalidate && self.payload_state.is_serving(&cache_key) {
+            self.payload_state.stale.store(true, Ordering::SeqCst);
+            if !self.payload_state.revalidating.swap(true, Ordering::SeqCst) {
+                let client = self.clone();
+                tokio::spawn(async move {
+                    client.fetch(&cache_key, remote_eval_request.as_ref()).await;
+                    client.payload_state.revalidating.store(false, Ordering::SeqCst);
+                });
             }
+            return;
+        }
+
+        self.fetch(&cache_key, remote_eval_request.as_ref()).await;
+    }
+
+    /// Whether the payload being served is past its TTL and hasn't been
+    /// revalidated yet, either because the background fetch is in flight or
+    /// because the API couldn't be reached.
+    pub fn is_stale(&self) -> bool {
+        self.payload_state.stale.load(Ordering::SeqCst)
+    }
+
+    async fn fetch(
+        &self,
+        cache_key: &str,
+        remote_eval_request: Option<&RemoteEvalRequest>,
+    ) {
+        let Some(gateway) = &self.gateway else {
+            return;
+        };
+
+        let fetched = match remote_eval_request {
+            Some(request) => gateway.post_remote_eval(request).await.map(Some),
+            None => self.fetch_features_if_modified(gateway).await,
+        };
+        match fetched {
+            Ok(None) => {
+                // Unchanged: keep the parsed features, just extend the TTL.
+                let last_response = self.last_fetched.lock().ok().and_then(|last| last.as_ref().map(|(_, response)| response.clone()));
+                if let (Some(cache), Some(response)) = (&self.cache, last_response) {
+                    cache.set(cache_key, response).await;
+                }
+                self.payload_state.mark_fresh(cache_key);
+            },
+            Ok(Some(response)) => {
+                // Update cache
+                if let Some(cache) = &self.cache {
+                    cache.set(cache_key, response.clone()).await;
+                }
+                self.update_gb(response);
+                self.payload_state.mark_fresh(cache_key);
+            },
+            Err(e) => {
+                error!("[growthbook-sdk] Failed to fetch features: {:?}", e);
+                // Nothing is being served yet (e.g. at startup): fall back to
+                // whatever the cache still has, however old.
+                if !self.payload_state.is_serving(cache_key) {
+                    if let Some(response) = self.stale_cached_payload(cache_key).await {
+                        self.update_gb(response);
+                        self.payload_state.mark_stale(cache_key);
+                    }
+                }
+            },
+        }
+    }
+
+    async fn stale_cached_payload(
+        &self,
+        cache_key: &str,
+    ) -> Option<GrowthBookResponse> {
+        self.cache.as_ref()?.get_stale(cache_key).await
+    }
+
+    // `None` when the server answered 304 Not Modified.
+    async fn fetch_features_if_modified(
+        &self,
+        gateway: &GrowthbookGateway,
+    ) -> Result<Option<GrowthBookResponse>, GrowthbookError> {
+        let validators = self.last_fetched.lock().ok().and_then(|last| last.as_ref().map(|(validators, _)| validators.clone()));
+        match gateway.get_features_if_modified(validators.as_ref()).await? {
+            FeaturesFetch::NotModified => Ok(None),
+            FeaturesFetch::Modified(response, validators) => {
+                if let Ok(mut last) = self.last_fetched.lock() {
+                    *last = (!validators.is_empty()).then(|| (validators, (*response).clone()));
+                }
+                Ok(Some(*response))
+            },
         }
     }
 
@@ -319,6 +705,7 @@ impl GrowthBookClient {
         response: GrowthBookResponse,
     ) {
         let mut features = response.features;
+        let mut experiments = response.experiments;
 
         if let Some(encrypted_features) = response.encrypted_features {
             if let Some(key) = &self.decryption_key {
@@ -339,21 +726,105 @@ impl GrowthBookClient {
             }
         }
 
-        let mut writable_config = self.gb.write().expect("problem to create mutex for gb data");
-        let attributes = writable_config.attributes.clone();
-        *writable_config = GrowthBook {
-            forced_variations: response.forced_variations,
-            features: features.unwrap_or_default(),
-            attributes,
-            sticky_bucket_service: writable_config.sticky_bucket_service.clone(),
-            saved_groups: saved_groups_from_value(response.saved_groups.as_ref()),
-        };
+        if let Some(encrypted_experiments) = response.encrypted_experiments {
+            if let Some(key) = &self.decryption_key {
+                match decrypt_features(&encrypted_experiments, key) {
+                    Ok(decrypted) => {
+                        if let Ok(parsed_experiments) = serde_json::from_str(&decrypted) {
+                            experiments = Some(parsed_experiments);
+                        } else {
+                            error!("[growthbook-sdk] Failed to parse decrypted experiments");
+                        }
+                    },
+                    Err(e) => {
+                        error!("[growthbook-sdk] Failed to decrypt experiments: {:?}", e);
+                    },
+                }
+            } else {
+                error!("[growthbook-sdk] Encrypted experiments received but no decryption key provided");
+            }
+        }
+
+        self.write_gb(|gb| {
+            gb.forced_variations = response.forced_variations;
+            gb.features = features.unwrap_or_default();
+            gb.saved_groups = saved_groups_from_value(response.saved_groups.as_ref());
+            gb.experiments = experiments.unwrap_or_default();
+        });
+        self.report_condition_errors();
 
         for callback in &self.on_refresh {
             callback();
         }
     }
 
+    /// Replaces the client-level attributes. In remote evaluation mode a
+    /// change re-fetches the payload evaluated for the new attributes.
+    pub async fn set_attributes(
+        &self,
+        attributes: HashMap<String, GrowthBookAttribute>,
+    ) {
+        let previous = self.remote_eval.then(|| self.remote_eval_request());
+        self.write_gb(|gb| gb.attributes = Some(attributes));
+        if previous.is_some_and(|previous| previous != self.remote_eval_request()) {
+            self.refresh().await;
+        }
+    }
+
+    // What remote evaluation posts: the client's attributes, forced
+    // variations and forced features.
+    fn remote_eval_request(&self) -> RemoteEvalRequest {
+        let gb = self.read_gb();
+        let attributes: Vec<GrowthBookAttribute> = gb.attributes.iter().flat_map(|attributes| attributes.values().cloned()).collect();
+        RemoteEvalRequest::new(&attributes, &gb.local_forced_variations, &gb.forced_features)
+    }
+
+    /// Replaces the forced features set on the builder. They are kept across
+    /// refreshes; pass an empty map to clear them.
+    pub fn set_forced_features(
+        &self,
+        forced_features: HashMap<String, Value>,
+    ) {
+        self.write_gb(|gb| gb.forced_features = Arc::new(forced_features));
+    }
+
+    /// Replaces the forced variations set on the builder. They are kept
+    /// across refreshes; pass an empty map to clear them.
+    pub fn set_forced_variations(
+        &self,
+        forced_variations: HashMap<String, i64>,
+    ) {
+        self.write_gb(|gb| gb.local_forced_variations = forced_variations);
+    }
+
+    /// Forces a single experiment variation, like JS `forceVariation`.
+    pub fn force_variation(
+        &self,
+        experiment_key: &str,
+        variation: i64,
+    ) {
+        self.write_gb(|gb| {
+            gb.local_forced_variations.insert(experiment_key.to_string(), variation);
+        });
+    }
+
+    /// Flips the experiment kill switch at runtime, e.g. during an analytics
+    /// outage. See [`GrowthBookClientBuilder::enabled`].
+    pub fn set_enabled(
+        &self,
+        enabled: bool,
+    ) {
+        self.write_gb(|gb| gb.enabled = enabled);
+    }
+
+    /// Turns QA mode on or off at runtime. See [`GrowthBookClientBuilder::qa_mode`].
+    pub fn set_qa_mode(
+        &self,
+        qa_mode: bool,
+    ) {
+        self.write_gb(|gb| gb.qa_mode = qa_mode);
+    }
+
     pub fn start_auto_refresh(&self) {
         let client = self.clone();
         tokio::spawn(async move {
@@ -364,6 +835,80 @@ impl GrowthBookClient {
         });
     }
 
+    /// Subscribes to streamed payloads in the background; see
+    /// [`GrowthBookClientBuilder::streaming`].
+    pub fn start_streaming(&self) {
+        let client = self.clone();
+        tokio::spawn(async move {
+            client.stream_features().await;
+        });
+    }
+
+    async fn stream_features(&self) {
+        let Some(gateway) = &self.gateway else {
+            return;
+        };
+        let mut backoff = Backoff::new(self.refresh_interval);
+
+        loop {
+            match gateway.subscribe().await {
+                Ok(mut response) => {
+                    let mut parser = SseParser::default();
+                    loop {
+                        match timeout(STREAM_IDLE_TIMEOUT, response.chunk()).await {
+                            Ok(Ok(Some(chunk))) => {
+                                for event in parser.push(&chunk) {
+                                    if event.event == "features" {
+                                        self.apply_streamed_payload(&event.data).await;
+                                        backoff.reset();
+                                    }
+                                }
+                            },
+                            Ok(Ok(None)) => break,
+                            Ok(Err(e)) => {
+                                error!("[growthbook-sdk] Feature stream failed: {:?}", e);
+                                break;
+                            },
+                            Err(_) => {
+                                error!("[growthbook-sdk] Feature stream idle for {:?}, reconnecting", STREAM_IDLE_TIMEOUT);
+                                break;
+                            },
+                        }
+                    }
+                },
+                Err(e) => {
+                    error!("[growthbook-sdk] Failed to subscribe to feature stream: {:?}", e);
+                },
+            }
+
+            // Poll while the stream is down so updates keep flowing.
+            self.refresh().await;
+            sleep(backoff.next_delay()).await;
+        }
+    }
+
+    async fn apply_streamed_payload(
+        &self,
+        data: &str,
+    ) {
+        match serde_json::from_str::<GrowthBookResponse>(data) {
+            Ok(response) => {
+                // A 304 must not bring back the payload fetched before this one.
+                if let Ok(mut last) = self.last_fetched.lock() {
+                    *last = None;
+                }
+                if let Some(cache) = &self.cache {
+                    cache.set("features", response.clone()).await;
+                }
+                self.update_gb(response);
+                self.payload_state.mark_fresh("features");
+            },
+            Err(e) => {
+                error!("[growthbook-sdk] Failed to parse streamed features: {:?}", e);
+            },
+        }
+    }
+
     // Keep existing new method for backward compatibility,
     // Old new: spawned a task immediately.
     pub async fn new(
@@ -386,28 +931,211 @@ impl GrowthBookClient {
         builder.build().await
     }
 
-    fn read_gb(&self) -> GrowthBook {
-        match self.gb.read() {
-            Ok(rw_read_guard) => (*rw_read_guard).clone(),
-            Err(e) => {
-                error!("{}", format!("[growthbook-sdk] problem to reading gb mutex data returning empty {:?}", e));
-                GrowthBook {
-                    forced_variations: None,
-                    features: HashMap::new(),
-                    attributes: None,
-                    sticky_bucket_service: None,
-                    saved_groups: SavedGroups::new(),
-                }
-            },
+    /// The snapshot evaluations currently run against. Holding it pins the
+    /// features; later refreshes don't affect it.
+    pub fn snapshot(&self) -> Arc<FeatureSnapshot> {
+        self.snapshots.load()
+    }
+
+    fn read_gb(&self) -> Arc<FeatureSnapshot> {
+        self.snapshots.load()
+    }
+
+    fn write_gb(
+        &self,
+        update: impl FnOnce(&mut GrowthBook),
+    ) {
+        self.snapshots.update(update);
+    }
+
+    // Fires on_experiment_viewed unless this exposure was already tracked.
+    fn track_experiment(
+        &self,
+        result: &ExperimentResult,
+    ) {
+        if let Some(cb) = &self.on_experiment_viewed {
+            if self.tracked_experiments.as_ref().map_or(true, |tracked| tracked.should_track(result)) {
+                cb(result.clone());
+            }
         }
     }
+
+    // Conditions are compiled as the payload is parsed; the parts that can't
+    // be evaluated are logged when they change, not on every refresh of the
+    // same payload.
+    fn report_condition_errors(&self) {
+        let condition_errors = self.read_gb().condition_errors();
+        let mut hasher = DefaultHasher::new();
+        condition_errors.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.reported_condition_errors.swap(hash, Ordering::Relaxed) == hash {
+            return;
+        }
+        for condition_error in condition_errors {
+            error!("[growthbook-sdk] Invalid condition at {}", condition_error);
+        }
+    }
+
+    // The async service can't be awaited from a synchronous getter, which
+    // then evaluates without sticky buckets; say so instead of silently
+    // re-bucketing users. Logged once per client, not on every evaluation.
+    fn report_skipped_sticky_buckets(
+        &self,
+        getter: &str,
+    ) {
+        if self.async_sticky_bucket_service.is_some() && !self.skipped_sticky_buckets_reported.swap(true, Ordering::Relaxed) {
+            error!(
+                "[growthbook-sdk] '{}' evaluates without sticky buckets because the sticky bucket service is async; use the async getters instead",
+                getter
+            );
+        }
+    }
+
     fn resolve_feature(
         &self,
         feature_name: &str,
         user_attributes: Option<Vec<GrowthBookAttribute>>,
     ) -> FeatureResult {
-        let result = self.read_gb().check(feature_name, &user_attributes);
+        self.report_skipped_sticky_buckets("feature_result");
+        let gb = self.read_gb();
+        self.resolve_feature_on(&gb, &gb.prepare_user(&user_attributes), feature_name)
+    }
+
+    fn resolve_feature_on(
+        &self,
+        gb: &GrowthBook,
+        user: &PreparedUser,
+        feature_name: &str,
+    ) -> FeatureResult {
+        let result = gb.check_prepared(feature_name, user);
+        for experiment_result in self.report_feature_usage(feature_name, &result) {
+            self.track_experiment(&experiment_result);
+        }
+
+        result
+    }
+
+    /// A [`UserContext`] evaluating against the current snapshot, with
+    /// `user_attributes` merged into the client's once for all its
+    /// evaluations. Sticky buckets come from the
+    /// [`GrowthBookClientBuilder::sticky_bucket_service`]; use
+    /// `for_user_async` with an async service.
+    pub fn for_user(
+        &self,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> UserContext {
+        self.report_skipped_sticky_buckets("for_user");
+        self.user_context(user_attributes)
+    }
+
+    fn user_context(
+        &self,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> UserContext {
+        let snapshot = self.read_gb();
+        let user = snapshot.prepare_user(&user_attributes);
+        UserContext {
+            client: self.clone(),
+            snapshot,
+            user,
+            sticky_buckets: None,
+        }
+    }
+
+    /// Like `for_user`, with the user's documents prefetched from the
+    /// [`GrowthBookClientBuilder::async_sticky_bucket_service`]. Evaluations
+    /// read and update the prefetched documents; save the changes with
+    /// [`UserContext::flush_sticky_buckets`].
+    pub async fn for_user_async(
+        &self,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> UserContext {
+        let mut context = self.user_context(user_attributes);
+        if let Some(service) = &self.async_sticky_bucket_service {
+            let attributes = context.snapshot.sticky_bucket_attributes_of(&context.user.attributes);
+            let sticky_buckets = Arc::new(PrefetchedStickyBuckets::new(service.get_all_assignments(&attributes).await));
+            context.user.sticky_bucket_service = Some(sticky_buckets.clone());
+            context.sticky_buckets = Some(sticky_buckets);
+        }
+        context
+    }
+
+    /// Like `feature_result`, with sticky buckets from the
+    /// [`GrowthBookClientBuilder::async_sticky_bucket_service`]: the user's
+    /// documents are prefetched, used for the evaluation, and the changed
+    /// ones saved before this returns.
+    pub async fn feature_result_async(
+        &self,
+        feature_name: &str,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> FeatureResult {
+        let context = self.for_user_async(user_attributes).await;
+        let result = context.feature_result(feature_name);
+        context.flush_sticky_buckets().await;
+        result
+    }
 
+    /// Like `evaluate_all`, with sticky buckets from the async service,
+    /// prefetched once for all features; see `feature_result_async`.
+    pub async fn evaluate_all_async(
+        &self,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> HashMap<String, FeatureResult> {
+        let context = self.for_user_async(user_attributes).await;
+        let results = context.evaluate_all();
+        context.flush_sticky_buckets().await;
+        results
+    }
+
+    /// Evaluates every feature for a user against a single snapshot of the
+    /// payload, e.g. to bootstrap a frontend. Callbacks fire as for
+    /// `feature_result`, except that an exposure shared by several features
+    /// (a holdout, say) is tracked once.
+    pub fn evaluate_all(
+        &self,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> HashMap<String, FeatureResult> {
+        self.evaluate_all_with_prefix("", user_attributes)
+    }
+
+    /// Like `evaluate_all`, limited to the features whose key starts with
+    /// `prefix`.
+    pub fn evaluate_all_with_prefix(
+        &self,
+        prefix: &str,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> HashMap<String, FeatureResult> {
+        self.report_skipped_sticky_buckets("evaluate_all");
+        let gb = self.read_gb();
+        self.evaluate_all_on(&gb, &gb.prepare_user(&user_attributes), prefix)
+    }
+
+    fn evaluate_all_on(
+        &self,
+        gb: &GrowthBook,
+        user: &PreparedUser,
+        prefix: &str,
+    ) -> HashMap<String, FeatureResult> {
+        let results = gb.evaluate_all_prepared(prefix, user);
+
+        let mut exposures = HashSet::new();
+        for (feature_name, result) in &results {
+            for experiment_result in self.report_feature_usage(feature_name, result) {
+                if exposures.insert(tracking_key(&experiment_result)) {
+                    self.track_experiment(&experiment_result);
+                }
+            }
+        }
+
+        results
+    }
+
+    // Fires on_feature_usage and returns the exposures the result carries.
+    fn report_feature_usage(
+        &self,
+        feature_name: &str,
+        result: &FeatureResult,
+    ) -> Vec<ExperimentResult> {
         // 1. Trigger on_feature_usage only for successful evaluations
         // Exclude: unknownFeature, prerequisite, cyclicPrerequisite
         let invalid_sources = ["unknownFeature", "prerequisite", "cyclicPrerequisite"];
@@ -417,19 +1145,90 @@ impl GrowthBookClient {
             }
         }
 
-        // 2. Trigger on_experiment_viewed only if in_experiment is true
-        if let Some(cb) = &self.on_experiment_viewed {
-            if let Some(experiment_result) = &result.experiment_result {
-                if experiment_result.in_experiment {
-                    cb(experiment_result.clone());
-                }
-            }
+        // 2. Trigger on_experiment_viewed only for users hashed into the
+        // experiment; forced and query-string variations aren't exposures
+        let mut exposures: Vec<ExperimentResult> = result.experiment_result.iter().filter(|it| it.in_experiment && it.hash_used).cloned().collect();
+
+        // 3. Force rules may carry exposures to track (JS `rule.tracks`)
+        exposures.extend(result.tracks.iter().map(|track| track.experiment_result()));
+
+        exposures
+    }
+
+    /// Runs an inline experiment against the current payload (the JS
+    /// `growthbook.run`). `on_experiment_viewed` fires only when the user was
+    /// hash-bucketed into the experiment, not for forced variations.
+    pub fn run(
+        &self,
+        experiment: &Experiment,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> ExperimentResult {
+        let gb = self.read_gb();
+        self.run_on(&gb, &gb.prepare_user(&user_attributes), experiment)
+    }
+
+    fn run_on(
+        &self,
+        gb: &GrowthBook,
+        user: &PreparedUser,
+        experiment: &Experiment,
+    ) -> ExperimentResult {
+        let result = gb.run_prepared(experiment, user);
+
+        if result.in_experiment && result.hash_used {
+            self.track_experiment(&result);
         }
 
         result
     }
+
+    /// Evaluates a feature like `feature_result` and returns the ordered
+    /// trace of every rule visited: why each was skipped, or that it
+    /// matched, with the hashes and buckets computed. Meant for debugging:
+    /// no tracking callbacks fire and sticky buckets are only read.
+    pub fn explain_feature(
+        &self,
+        feature_name: &str,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> FeatureExplanation {
+        self.read_gb().explain(feature_name, &user_attributes)
+    }
+
+    /// Runs the payload's URL redirect experiments for a user on
+    /// `current_url` and returns where to send them, if anywhere. Exposures
+    /// are tracked for every experiment the user is bucketed into, including
+    /// the control, so check the result before the redirect happens.
+    pub fn get_redirect_url(
+        &self,
+        current_url: &str,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> Option<RedirectResult> {
+        self.report_skipped_sticky_buckets("get_redirect_url");
+        let gb = self.read_gb();
+        self.get_redirect_url_on(&gb, &gb.prepare_user(&user_attributes), current_url)
+    }
+
+    fn get_redirect_url_on(
+        &self,
+        gb: &GrowthBook,
+        user: &PreparedUser,
+        current_url: &str,
+    ) -> Option<RedirectResult> {
+        let (results, redirect) = gb.run_redirect_experiments_prepared(current_url, user);
+
+        for result in results.iter().filter(|it| it.hash_used) {
+            self.track_experiment(result);
+        }
+
+        redirect
+    }
 }
 
+/// Synchronous feature evaluation. With an
+/// [`GrowthBookClientBuilder::async_sticky_bucket_service`] these getters
+/// can't wait on the service, so they evaluate without sticky buckets (and
+/// the first one to do so logs an error); use `GrowthBookClient::feature_result_async`,
+/// `evaluate_all_async` or `for_user_async` instead.
 pub trait GrowthBookClientTrait: Debug + Send + Sync {
     fn is_on(
         &self,
@@ -450,6 +1249,106 @@ pub trait GrowthBookClientTrait: Debug + Send + Sync {
     ) -> FeatureResult;
 
     fn total_features(&self) -> usize;
+
+    /// The feature's value as `T` (JS `getFeatureValue`). Unknown features
+    /// and null values return `default`; a value of another type returns
+    /// `default` and is reported through `report_feature_value_mismatch`.
+    fn get_feature_value<T: DeserializeOwned>(
+        &self,
+        feature_name: &str,
+        default: T,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> T
+    where
+        Self: Sized,
+    {
+        typed_value(self, feature_name, default, user_attributes)
+    }
+
+    fn get_bool(
+        &self,
+        feature_name: &str,
+        default: bool,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> bool {
+        typed_value(self, feature_name, default, user_attributes)
+    }
+
+    fn get_string(
+        &self,
+        feature_name: &str,
+        default: &str,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> String {
+        typed_value(self, feature_name, default.to_string(), user_attributes)
+    }
+
+    fn get_i64(
+        &self,
+        feature_name: &str,
+        default: i64,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> i64 {
+        typed_value(self, feature_name, default, user_attributes)
+    }
+
+    fn get_f64(
+        &self,
+        feature_name: &str,
+        default: f64,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> f64 {
+        typed_value(self, feature_name, default, user_attributes)
+    }
+
+    fn get_json(
+        &self,
+        feature_name: &str,
+        default: Value,
+        user_attributes: Option<Vec<GrowthBookAttribute>>,
+    ) -> Value {
+        typed_value(self, feature_name, default, user_attributes)
+    }
+
+    /// Reports a typed getter's type mismatch. Logs by default.
+    fn report_feature_value_mismatch(
+        &self,
+        feature_name: &str,
+        _result: &FeatureResult,
+        error: &GrowthbookError,
+    ) {
+        error!("[growthbook-sdk] Feature '{}' has an unexpected value type, using the default: {}", feature_name, error);
+    }
+}
+
+// Shared by the typed getters; takes `?Sized` so the non-generic ones keep the
+// trait usable as `dyn GrowthBookClientTrait`.
+fn typed_value<T: DeserializeOwned, C: GrowthBookClientTrait + ?Sized>(
+    client: &C,
+    feature_name: &str,
+    default: T,
+    user_attributes: Option<Vec<GrowthBookAttribute>>,
+) -> T {
+    typed_result(client, feature_name, client.feature_result(feature_name, user_attributes), default)
+}
+
+fn typed_result<T: DeserializeOwned, C: GrowthBookClientTrait + ?Sized>(
+    client: &C,
+    feature_name: &str,
+    result: FeatureResult,
+    default: T,
+) -> T {
+    if result.value.is_null() {
+        return default;
+    }
+
+    match result.value_as::<T>() {
+        Ok(value) => value,
+        Err(error) => {
+            client.report_feature_value_mismatch(feature_name, &result, &error);
+            default
+        },
+    }
 }
 
 impl GrowthBookClientTrait for GrowthBookClient {
@@ -481,6 +1380,162 @@ impl GrowthBookClientTrait for GrowthBookClient {
         let gb_data = self.read_gb();
         gb_data.features.len()
     }
+
+    fn report_feature_value_mismatch(
+        &self,
+        feature_name: &str,
+        result: &FeatureResult,
+        error: &GrowthbookError,
+    ) {
+        match &self.on_feature_value_mismatch {
+            Some(cb) => cb(feature_name.to_string(), result.clone(), error.clone()),
+            None => {
+                error!("[growthbook-sdk] Feature '{}' has an unexpected value type, using the default: {}", feature_name, error);
+            },
+        }
+    }
+}
+
+/// One user's evaluations against a pinned snapshot, e.g. for the duration
+/// of a request. The user's attributes are merged with the client's, and the
+/// forced variations resolved, once when it's created, instead of on every
+/// evaluation. Callbacks and exposure tracking go through the client, which
+/// the context holds a clone of, so it can be moved into a spawned task.
+#[derive(Debug)]
+pub struct UserContext {
+    client: GrowthBookClient,
+    snapshot: Arc<FeatureSnapshot>,
+    user: PreparedUser,
+    sticky_buckets: Option<Arc<PrefetchedStickyBuckets>>,
+}
+
+impl UserContext {
+    /// The snapshot this context evaluates against; refreshes after it was
+    /// created don't affect it.
+    pub fn snapshot(&self) -> &Arc<FeatureSnapshot> {
+        &self.snapshot
+    }
+
+    /// The user's attributes, merged with the client's.
+    pub fn attributes(&self) -> &[GrowthBookAttribute] {
+        &self.user.attributes
+    }
+
+    pub fn is_on(
+        &self,
+        feature_name: &str,
+    ) -> bool {
+        self.feature_result(feature_name).on
+    }
+
+    pub fn is_off(
+        &self,
+        feature_name: &str,
+    ) -> bool {
+        self.feature_result(feature_name).off
+    }
+
+    pub fn feature_result(
+        &self,
+        feature_name: &str,
+    ) -> FeatureResult {
+        self.client.resolve_feature_on(&self.snapshot, &self.user, feature_name)
+    }
+
+    /// The feature's value as `T`; see
+    /// [`GrowthBookClientTrait::get_feature_value`].
+    pub fn get_feature_value<T: DeserializeOwned>(
+        &self,
+        feature_name: &str,
+        default: T,
+    ) -> T {
+        typed_result(&self.client, feature_name, self.feature_result(feature_name), default)
+    }
+
+    pub fn get_bool(
+        &self,
+        feature_name: &str,
+        default: bool,
+    ) -> bool {
+        self.get_feature_value(feature_name, default)
+    }
+
+    pub fn get_string(
+        &self,
+        feature_name: &str,
+        default: &str,
+    ) -> String {
+        self.get_feature_value(feature_name, default.to_string())
+    }
+
+    pub fn get_i64(
+        &self,
+        feature_name: &str,
+        default: i64,
+    ) -> i64 {
+        self.get_feature_value(feature_name, default)
+    }
+
+    pub fn get_f64(
+        &self,
+        feature_name: &str,
+        default: f64,
+    ) -> f64 {
+        self.get_feature_value(feature_name, default)
+    }
+
+    pub fn get_json(
+        &self,
+        feature_name: &str,
+        default: Value,
+    ) -> Value {
+        self.get_feature_value(feature_name, default)
+    }
+
+    /// See [`GrowthBookClient::evaluate_all`].
+    pub fn evaluate_all(&self) -> HashMap<String, FeatureResult> {
+        self.evaluate_all_with_prefix("")
+    }
+
+    pub fn evaluate_all_with_prefix(
+        &self,
+        prefix: &str,
+    ) -> HashMap<String, FeatureResult> {
+        self.client.evaluate_all_on(&self.snapshot, &self.user, prefix)
+    }
+
+    /// See [`GrowthBookClient::run`].
+    pub fn run(
+        &self,
+        experiment: &Experiment,
+    ) -> ExperimentResult {
+        self.client.run_on(&self.snapshot, &self.user, experiment)
+    }
+
+    /// See [`GrowthBookClient::explain_feature`].
+    pub fn explain_feature(
+        &self,
+        feature_name: &str,
+    ) -> FeatureExplanation {
+        self.snapshot.explain_prepared(feature_name, &self.user)
+    }
+
+    /// See [`GrowthBookClient::get_redirect_url`].
+    pub fn get_redirect_url(
+        &self,
+        current_url: &str,
+    ) -> Option<RedirectResult> {
+        self.client.get_redirect_url_on(&self.snapshot, &self.user, current_url)
+    }
+
+    /// Saves the sticky bucket documents changed by this context's
+    /// evaluations to the async service. Does nothing for a context created
+    /// by `for_user`.
+    pub async fn flush_sticky_buckets(&self) {
+        if let (Some(service), Some(sticky_buckets)) = (&self.client.async_sticky_bucket_service, &self.sticky_buckets) {
+            sticky_buckets.flush(service.as_ref()).await;
+        }
+    }
 }
 
 use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
@@ -0,0 +1,581 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::condition::eval_context::ConditionEvalContext;
+use crate::condition::operator_condition::{AllOperand, EqOperand, InOperand, OperatorCondition};
+use crate::condition::order_comparison::{OrderComparison, OrderOperand, OrderOperator};
+use crate::condition::regex_comparison::RegexComparison;
+use crate::condition::size_comparison::SizeComparison;
+use crate::condition::type_comparison::TypeComparison;
+use crate::condition::version_comparison::{VersionComparison, VersionOperator};
+use crate::model_public::{GrowthBookAttribute, GrowthBookAttributeValue};
+
+/// A targeting condition, compiled once when it's loaded: operators are
+/// resolved, regexes compiled, versions normalized and `$in` lists hashed,
+/// so evaluating it only looks up the user's attributes. Serializes back to
+/// the condition it was compiled from.
+#[derive(Clone)]
+pub struct Condition {
+    source: Value,
+    nodes: Arc<[Node]>,
+    errors: Arc<[ConditionError]>,
+}
+
+/// A part of a condition that can't be evaluated as written, e.g. an unknown
+/// operator or a regex that doesn't compile. It fails to match (a negated
+/// one, like `$notRegex`, always matches), as it did at evaluation time.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConditionError {
+    /// Where the error is, e.g. `country.$regex`; empty for the condition
+    /// itself.
+    pub path: String,
+    pub message: String,
+}
+
+impl ConditionError {
+    fn new(
+        path: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// The same error, located under `prefix` (e.g. the rule it belongs to).
+    pub(crate) fn within(
+        self,
+        prefix: &str,
+    ) -> Self {
+        Self {
+            path: join(prefix, &self.path),
+            message: self.message,
+        }
+    }
+}
+
+impl Display for ConditionError {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl Condition {
+    /// Compiles a condition. One that isn't an object is reported and
+    /// matches everyone, as if there were no condition.
+    pub fn compile(source: Value) -> Self {
+        let attributes = GrowthBookAttribute::from(source.clone()).ok();
+        Self::build(source, attributes.as_deref())
+    }
+
+    fn build(
+        source: Value,
+        attributes: Option<&[GrowthBookAttribute]>,
+    ) -> Self {
+        let mut compiler = Compiler::default();
+        let nodes = match attributes {
+            Some(attributes) => compiler.compile_all("", None, attributes, false),
+            None => {
+                compiler.error("", "expected an object");
+                Vec::new()
+            },
+        };
+
+        Self {
+            source,
+            nodes: nodes.into(),
+            errors: compiler.errors.into(),
+        }
+    }
+
+    pub fn matches(
+        &self,
+        ctx: &ConditionEvalContext,
+    ) -> bool {
+        self.nodes.iter().all(|node| node.matches(ctx))
+    }
+
+    /// The condition as it appears in the payload.
+    pub fn source(&self) -> &Value {
+        &self.source
+    }
+
+    /// What couldn't be compiled; empty for a valid condition.
+    pub fn errors(&self) -> &[ConditionError] {
+        &self.errors
+    }
+}
+
+impl Debug for Condition {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        f.debug_tuple("Condition").field(&self.source).finish()
+    }
+}
+
+impl Serialize for Condition {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.source.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Condition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(Condition::compile)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Not,
+    And,
+    Nor,
+    Or,
+    Eq,
+    Ne,
+    In,
+    Nin,
+    Ini,
+    Nini,
+    All,
+    Alli,
+    Order(OrderOperator),
+    Version(VersionOperator),
+    Exists,
+    Regex,
+    Regexi,
+    NotRegex,
+    NotRegexi,
+    Type,
+    Size,
+    ElemMatch,
+    InGroup,
+    NotInGroup,
+}
+
+impl Operator {
+    fn parse(key: &str) -> Option<Self> {
+        let operator = match key {
+            "$not" => Operator::Not,
+            "$and" => Operator::And,
+            "$nor" => Operator::Nor,
+            "$or" => Operator::Or,
+            "$eq" => Operator::Eq,
+            "$ne" => Operator::Ne,
+            "$in" => Operator::In,
+            "$nin" => Operator::Nin,
+            "$ini" => Operator::Ini,
+            "$nini" => Operator::Nini,
+            "$all" => Operator::All,
+            "$alli" => Operator::Alli,
+            "$gt" => Operator::Order(OrderOperator::Gt),
+            "$gte" => Operator::Order(OrderOperator::Gte),
+            "$lt" => Operator::Order(OrderOperator::Lt),
+            "$lte" => Operator::Order(OrderOperator::Lte),
+            "$vgt" => Operator::Version(VersionOperator::Gt),
+            "$vgte" => Operator::Version(VersionOperator::Gte),
+            "$vlt" => Operator::Version(VersionOperator::Lt),
+            "$vlte" => Operator::Version(VersionOperator::Lte),
+            "$veq" => Operator::Version(VersionOperator::Eq),
+            "$vne" => Operator::Version(VersionOperator::Ne),
+            "$exists" => Operator::Exists,
+            "$regex" => Operator::Regex,
+            "$regexi" => Operator::Regexi,
+            "$notRegex" => Operator::NotRegex,
+            "$notRegexi" => Operator::NotRegexi,
+            "$type" => Operator::Type,
+            "$size" => Operator::Size,
+            "$elemMatch" => Operator::ElemMatch,
+            "$inGroup" => Operator::InGroup,
+            "$notInGroup" => Operator::NotInGroup,
+            _ => return None,
+        };
+        Some(operator)
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    /// Every node matches: an object of conditions on a field, or `$size`
+    /// holding a condition.
+    All(Vec<Node>),
+    /// `$elemMatch`: any node matches.
+    Any(Vec<Node>),
+    /// `$not`: no node matches.
+    Not(Vec<Node>),
+    And(Vec<Vec<Node>>),
+    Nor(Vec<Vec<Node>>),
+    Or(Vec<Vec<Node>>),
+    /// A test of the user's value at `path`.
+    Test {
+        path: Vec<String>,
+        test: Test,
+    },
+    /// The fixed outcome of an invalid condition.
+    Const(bool),
+}
+
+impl Node {
+    fn matches(
+        &self,
+        ctx: &ConditionEvalContext,
+    ) -> bool {
+        match self {
+            Node::All(nodes) => nodes.iter().all(|node| node.matches(ctx)),
+            Node::Any(nodes) => nodes.iter().any(|node| node.matches(ctx)),
+            Node::Not(nodes) => nodes.iter().all(|node| !node.matches(ctx)),
+            Node::And(conditions) => conditions.iter().all(|nodes| nodes.iter().all(|node| node.matches(ctx))),
+            Node::Nor(conditions) => conditions.iter().all(|nodes| !nodes.iter().all(|node| node.matches(ctx))),
+            Node::Or(conditions) => conditions.iter().any(|nodes| nodes.iter().all(|node| node.matches(ctx))),
+            Node::Test { path, test } => test.matches(ctx.lookup(path), ctx),
+            Node::Const(result) => *result,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Test {
+    Eq(EqOperand),
+    Ne(EqOperand),
+    In(InOperand),
+    Nin(InOperand),
+    All(AllOperand),
+    Order(OrderOperator, OrderOperand, bool),
+    Version(VersionOperator, String),
+    Exists(bool),
+    Regex(Regex),
+    NotRegex(Regex),
+    Type(String),
+    Size(i64),
+    InGroup(String),
+    NotInGroup(String),
+    /// A plain array: the user's array holds the same items, in order.
+    ArrayEquals(Vec<GrowthBookAttributeValue>),
+    /// A plain `{}`: the user's value is an empty object.
+    EmptyObject,
+    /// A plain `null`: the user's value is missing or `null`.
+    Null,
+    /// A plain number or boolean.
+    Equals(GrowthBookAttributeValue),
+}
+
+impl Test {
+    fn matches(
+        &self,
+        user_value: Option<&GrowthBookAttributeValue>,
+        ctx: &ConditionEvalContext,
+    ) -> bool {
+        match self {
+            Test::Eq(operand) => OperatorCondition::eq(operand, user_value),
+            Test::Ne(operand) => OperatorCondition::ne(operand, user_value),
+            Test::In(operand) => OperatorCondition::is_in(operand, user_value),
+            Test::Nin(operand) => OperatorCondition::nin(operand, user_value),
+            Test::All(operand) => OperatorCondition::all(operand, user_value),
+            Test::Order(operator, operand, array_size) => OrderComparison::matches(*operator, operand, user_value, *array_size),
+            Test::Version(operator, version) => VersionComparison::matches(*operator, version, user_value),
+            Test::Exists(expected) => OperatorCondition::exists(*expected, user_value),
+            Test::Regex(regex) => RegexComparison::matches(regex, user_value),
+            Test::NotRegex(regex) => !RegexComparison::matches(regex, user_value),
+            Test::Type(feature_type) => TypeComparison::matches(feature_type, user_value),
+            Test::Size(size) => SizeComparison::matches(*size, user_value),
+            Test::InGroup(group_id) => OperatorCondition::in_group(group_id, user_value, ctx),
+            Test::NotInGroup(group_id) => !OperatorCondition::in_group(group_id, user_value, ctx),
+            Test::ArrayEquals(feature_values) => match user_value {
+                Some(GrowthBookAttributeValue::Array(user_values)) => feature_values == user_values,
+                _ => false,
+            },
+            Test::EmptyObject => matches!(user_value, Some(GrowthBookAttributeValue::Object(user_object)) if user_object.is_empty()),
+            Test::Null => user_value.map_or(true, |it| it == &GrowthBookAttributeValue::Empty),
+            Test::Equals(feature_value) => user_value.is_some_and(|it| it == feature_value),
+        }
+    }
+}
+
+// Compiles conditions node by node. The attribute a node tests is its
+// parent's key when it's nested in a field's object (`{country: {$in: [..]}}`)
+// and its own key otherwise; `location` only tracks where errors are.
+#[derive(Default)]
+struct Compiler {
+    errors: Vec<ConditionError>,
+}
+
+impl Compiler {
+    fn compile_all(
+        &mut self,
+        location: &str,
+        parent: Option<&str>,
+        attributes: &[GrowthBookAttribute],
+        array_size: bool,
+    ) -> Vec<Node> {
+        attributes.iter().map(|attribute| self.compile(location, parent, attribute, array_size)).collect()
+    }
+
+    fn compile(
+        &mut self,
+        location: &str,
+        parent: Option<&str>,
+        attribute: &GrowthBookAttribute,
+        array_size: bool,
+    ) -> Node {
+        let location = join(location, &attribute.key);
+        let Some(operator) = Operator::parse(&attribute.key) else {
+            return self.field(&location, parent, attribute);
+        };
+
+        let path = parent.unwrap_or(&attribute.key);
+        let value = &attribute.value;
+        match operator {
+            Operator::Not => match value {
+                GrowthBookAttributeValue::Object(children) => Node::Not(self.compile_all(&location, parent, children, false)),
+                _ => self.invalid(&location, "expected an object", false),
+            },
+            Operator::And | Operator::Nor => match value {
+                GrowthBookAttributeValue::Array(items) => {
+                    let conditions = self.sub_conditions(&location, items);
+                    // A sub-condition that isn't an object fails both.
+                    match conditions.into_iter().collect::<Option<Vec<_>>>() {
+                        Some(conditions) if operator == Operator::And => Node::And(conditions),
+                        Some(conditions) => Node::Nor(conditions),
+                        None => Node::Const(false),
+                    }
+                },
+                _ => self.invalid(&location, "expected an array", false),
+            },
+            Operator::Or => match value {
+                GrowthBookAttributeValue::Array(items) if items.is_empty() => Node::Const(true),
+                GrowthBookAttributeValue::Array(items) => {
+                    let conditions = self.sub_conditions(&location, items);
+                    Node::Or(conditions.into_iter().map(|nodes| nodes.unwrap_or_else(|| vec![Node::Const(false)])).collect())
+                },
+                GrowthBookAttributeValue::Empty => Node::Const(true),
+                _ => self.invalid(&location, "expected an array", false),
+            },
+            Operator::Eq => test(path, Test::Eq(EqOperand::new(value.clone()))),
+            Operator::Ne => test(path, Test::Ne(EqOperand::new(value.clone()))),
+            Operator::In | Operator::Nin | Operator::Ini | Operator::Nini => match value {
+                GrowthBookAttributeValue::Array(items) => {
+                    let operand = InOperand::new(items, matches!(operator, Operator::Ini | Operator::Nini));
+                    test(
+                        path,
+                        if matches!(operator, Operator::In | Operator::Ini) {
+                            Test::In(operand)
+                        } else {
+                            Test::Nin(operand)
+                        },
+                    )
+                },
+                _ => self.invalid(&location, "expected an array", false),
+            },
+            Operator::All | Operator::Alli => match value {
+                GrowthBookAttributeValue::Array(items) => test(path, Test::All(AllOperand::new(items, operator == Operator::Alli))),
+                _ => self.invalid(&location, "expected an array", false),
+            },
+            Operator::Order(order) => test(path, Test::Order(order, OrderComparison::compile(value), array_size)),
+            Operator::Version(version) => test(path, Test::Version(version, VersionComparison::compile(value))),
+            Operator::Exists => match value {
+                GrowthBookAttributeValue::Bool(expected) => test(path, Test::Exists(*expected)),
+                _ => self.invalid(&location, "expected a boolean", true),
+            },
+            Operator::Regex | Operator::Regexi | Operator::NotRegex | Operator::NotRegexi => {
+                let negated = matches!(operator, Operator::NotRegex | Operator::NotRegexi);
+                match RegexComparison::compile(value, matches!(operator, Operator::Regexi | Operator::NotRegexi)) {
+                    Ok(regex) if negated => test(path, Test::NotRegex(regex)),
+                    Ok(regex) => test(path, Test::Regex(regex)),
+                    Err(message) => self.invalid(&location, message, negated),
+                }
+            },
+            Operator::Type => match value {
+                GrowthBookAttributeValue::String(feature_type) => test(path, Test::Type(feature_type.clone())),
+                _ => self.invalid(&location, "expected a string", false),
+            },
+            Operator::Size => match value {
+                GrowthBookAttributeValue::Int(size) => test(path, Test::Size(*size)),
+                GrowthBookAttributeValue::Object(children) => Node::All(self.compile_all(&location, parent, children, true)),
+                _ => self.invalid(&location, "expected an integer or a condition", false),
+            },
+            Operator::ElemMatch => match value {
+                GrowthBookAttributeValue::Object(children) => Node::Any(self.compile_all(&location, parent, children, array_size)),
+                _ => self.invalid(&location, "expected an object", false),
+            },
+            Operator::InGroup | Operator::NotInGroup => match value {
+                GrowthBookAttributeValue::String(group_id) if operator == Operator::InGroup => test(path, Test::InGroup(group_id.clone())),
+                GrowthBookAttributeValue::String(group_id) => test(path, Test::NotInGroup(group_id.clone())),
+                _ => self.invalid(&location, "expected a saved group id", operator == Operator::NotInGroup),
+            },
+        }
+    }
+
+    // A key that isn't an operator names an attribute, tested against the
+    // value as a plain (non-operator) condition.
+    fn field(
+        &mut self,
+        location: &str,
+        parent: Option<&str>,
+        attribute: &GrowthBookAttribute,
+    ) -> Node {
+        // `$`-keys are attribute names only where a field is expected, and
+        // never compare to a string.
+        if attribute.key.starts_with('$') && (parent.is_some() || matches!(attribute.value, GrowthBookAttributeValue::String(_))) {
+            return self.invalid(location, format!("unknown operator `{}`", attribute.key), false);
+        }
+
+        let path = parent.unwrap_or(&attribute.key);
+        match &attribute.value {
+            GrowthBookAttributeValue::String(_) => test(path, Test::Eq(EqOperand::new(attribute.value.clone()))),
+            GrowthBookAttributeValue::Array(items) => test(path, Test::ArrayEquals(items.clone())),
+            GrowthBookAttributeValue::Object(children) if children.is_empty() => test(path, Test::EmptyObject),
+            GrowthBookAttributeValue::Object(children) => {
+                let parent = parent.map_or(attribute.key.clone(), |parent| format!("{}.{}", parent, attribute.key));
+                Node::All(self.compile_all(location, Some(&parent), children, false))
+            },
+            GrowthBookAttributeValue::Empty => test(path, Test::Null),
+            it => test(path, Test::Equals(it.clone())),
+        }
+    }
+
+    // The sub-conditions of `$and`, `$or` and `$nor`, which test their own
+    // keys; `None` for an item that isn't an object.
+    fn sub_conditions(
+        &mut self,
+        location: &str,
+        items: &[GrowthBookAttributeValue],
+    ) -> Vec<Option<Vec<Node>>> {
+        items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let location = format!("{location}[{index}]");
+                match item {
+                    GrowthBookAttributeValue::Object(children) => Some(self.compile_all(&location, None, children, false)),
+                    _ => {
+                        self.error(&location, "expected an object");
+                        None
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn invalid(
+        &mut self,
+        location: &str,
+        message: impl Into<String>,
+        result: bool,
+    ) -> Node {
+        self.error(location, message);
+        Node::Const(result)
+    }
+
+    fn error(
+        &mut self,
+        location: &str,
+        message: impl Into<String>,
+    ) {
+        self.errors.push(ConditionError::new(location, message));
+    }
+}
+
+fn test(
+    path: &str,
+    test: Test,
+) -> Node {
+    Node::Test {
+        path: path.split('.').map(String::from).collect(),
+        test,
+    }
+}
+
+fn join(
+    prefix: &str,
+    key: &str,
+) -> String {
+    match (prefix.is_empty(), key.is_empty()) {
+        (true, _) => key.to_string(),
+        (_, true) => prefix.to_string(),
+        _ => format!("{prefix}.{key}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use serde::Deserialize;
+    use serde_json::Value;
+
+    use crate::condition::compiled::Condition;
+    use crate::condition::eval_context::{saved_groups_from_value, ConditionEvalContext};
+    use crate::model_public::{GrowthBookAttribute, UserAttributes};
+
+    #[tokio::test]
+    async fn evaluate_conditions() -> Result<(), Box<dyn std::error::Error>> {
+        let cases = Cases::new();
+
+        for value in cases.eval_condition {
+            let eval_condition = EvalCondition::new(value);
+
+            let condition = Condition::compile(eval_condition.condition);
+            let attributes = UserAttributes::new(GrowthBookAttribute::from(eval_condition.attribute).expect("Failed to create attributes"));
+            let saved_groups = saved_groups_from_value(eval_condition.saved_groups.as_ref());
+            let enabled = condition.matches(&ConditionEvalContext::new(&attributes, &saved_groups));
+            if enabled != eval_condition.result {
+                panic!("EvalCondition failed: {}", eval_condition.name)
+            }
+        }
+
+        Ok(())
+    }
+
+    #[derive(Deserialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    struct Cases {
+        eval_condition: Vec<Value>,
+    }
+
+    pub struct EvalCondition {
+        name: String,
+        condition: Value,
+        attribute: Value,
+        result: bool,
+        saved_groups: Option<Value>,
+    }
+
+    impl EvalCondition {
+        fn new(value: Value) -> Self {
+            let array = value.as_array().expect("Failed to convert to array");
+            Self {
+                name: array[0].as_str().expect("Failed to convert do str").to_string(),
+                condition: array[1].clone(),
+                attribute: array[2].clone(),
+                result: array[3].as_bool().expect("Failed to convert to bool"),
+                // Optional 5th element: the savedGroups map for $inGroup/$notInGroup.
+                saved_groups: array.get(4).cloned(),
+            }
+        }
+    }
+
+    impl Cases {
+        pub fn new() -> Self {
+            let contents = fs::read_to_string("./tests/all_cases.json").expect("Should have been able to read the file");
+
+            serde_json::from_str(&contents).expect("Failed to create cases")
+        }
+    }
+}
@@ -2,8 +2,8 @@ use std::collections::HashMap;
 
 use serde_json::Value;
 
-use crate::extensions::FindGrowthBookAttribute;
-use crate::model_public::{GrowthBookAttribute, GrowthBookAttributeValue};
+use crate::extensions::{find_indexed_path, FindGrowthBookAttribute};
+use crate::model_public::{GrowthBookAttributeValue, UserAttributes};
 
 /// Saved groups available to condition evaluation: a group id mapped to its
 /// list of member values. Used by the `$inGroup` / `$notInGroup` operators.
@@ -14,18 +14,26 @@ pub type SavedGroups = HashMap<String, Vec<GrowthBookAttributeValue>>;
 /// (rather than threaded as separate params) so new evaluation inputs can be
 /// added without re-touching every operator signature.
 pub struct ConditionEvalContext<'a> {
-    attributes: &'a [GrowthBookAttribute],
+    attributes: &'a UserAttributes,
     saved_groups: &'a SavedGroups,
 }
 
 impl<'a> ConditionEvalContext<'a> {
     pub fn new(
-        attributes: &'a [GrowthBookAttribute],
+        attributes: &'a UserAttributes,
         saved_groups: &'a SavedGroups,
     ) -> Self {
         Self { attributes, saved_groups }
     }
 
+    /// The user's value at an attribute path, split into its parts.
+    pub(crate) fn lookup(
+        &self,
+        path: &[String],
+    ) -> Option<&'a GrowthBookAttributeValue> {
+        find_indexed_path(self.attributes, path)
+    }
+
     /// Members of a saved group by id, if the group is known.
     pub fn saved_group(
         &self,
@@ -35,8 +43,7 @@ impl<'a> ConditionEvalContext<'a> {
     }
 }
 
-// Lets every existing `ctx.find_value(key)` call site keep working after the
-// parameter type changed from `&[GrowthBookAttribute]` to `&ConditionEvalContext`.
+// Looks up a dotted attribute key, cloning the value.
 impl FindGrowthBookAttribute for ConditionEvalContext<'_> {
     fn find_value(
         &self,
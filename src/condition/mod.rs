@@ -1,9 +1,8 @@
-mod elem_match_comparison;
+pub mod compiled;
 pub mod eval_context;
 mod operator_condition;
 mod order_comparison;
 mod regex_comparison;
 mod size_comparison;
 mod type_comparison;
-pub mod use_case;
 mod version_comparison;
@@ -1,244 +1,163 @@
+use std::collections::HashSet;
+
 use crate::condition::eval_context::ConditionEvalContext;
-use crate::extensions::FindGrowthBookAttribute;
-use crate::model_public::{GrowthBookAttribute, GrowthBookAttributeValue};
+use crate::model_public::GrowthBookAttributeValue;
 
-pub struct OperatorCondition;
+/// The operand of `$eq` and `$ne` (and of a plain string condition), with
+/// its flattened string form for comparing against objects.
+#[derive(Clone, Debug)]
+pub struct EqOperand {
+    value: GrowthBookAttributeValue,
+    flattened: String,
+}
 
-impl OperatorCondition {
-    pub fn not(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
-    ) -> bool {
-        match &feature_attribute.value {
-            GrowthBookAttributeValue::Object(it) => it.iter().all(|next| !recursive(parent_attribute, next, ctx, false)),
-            _ => false,
-        }
+impl EqOperand {
+    pub fn new(value: GrowthBookAttributeValue) -> Self {
+        let flattened = value.to_string();
+        Self { value, flattened }
     }
+}
 
-    pub fn and(
-        _parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
-    ) -> bool {
-        and_nor(&feature_attribute, ctx, recursive, false)
+/// The list of `$in`, `$nin` and their case-insensitive forms, as the set of
+/// its items' string forms (lowercased for `$ini` / `$nini`).
+#[derive(Clone, Debug)]
+pub struct InOperand {
+    items: HashSet<String>,
+    case_insensitive: bool,
+}
+
+impl InOperand {
+    pub fn new(
+        items: &[GrowthBookAttributeValue],
+        case_insensitive: bool,
+    ) -> Self {
+        let items = items.iter().map(|item| in_key(item, case_insensitive)).collect();
+        Self { items, case_insensitive }
     }
 
-    pub fn nor(
-        _parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
+    fn contains(
+        &self,
+        user_value: &GrowthBookAttributeValue,
     ) -> bool {
-        and_nor(&feature_attribute, ctx, recursive, true)
+        self.items.contains(&in_key(user_value, self.case_insensitive))
     }
+}
 
-    pub fn all(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
+fn in_key(
+    value: &GrowthBookAttributeValue,
+    case_insensitive: bool,
+) -> String {
+    if case_insensitive {
+        value.to_string().to_lowercase()
+    } else {
+        value.to_string()
+    }
+}
+
+/// The list of `$all` / `$alli`; for `$alli` its strings are lowercased.
+#[derive(Clone, Debug)]
+pub struct AllOperand {
+    items: Vec<GrowthBookAttributeValue>,
+    case_insensitive: bool,
+}
+
+impl AllOperand {
+    pub fn new(
+        items: &[GrowthBookAttributeValue],
         case_insensitive: bool,
-        _recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
-    ) -> bool {
-        match &feature_attribute.value {
-            GrowthBookAttributeValue::Array(feature_values) => {
-                if let Some(GrowthBookAttributeValue::Array(user_values)) = ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key) {
-                    feature_values.iter().all(|feature_item| {
-                        user_values.iter().any(|user_item| {
-                            if case_insensitive {
-                                match (feature_item, user_item) {
-                                    (GrowthBookAttributeValue::String(f), GrowthBookAttributeValue::String(u)) => f.to_lowercase() == u.to_lowercase(),
-                                    _ => feature_item == user_item,
-                                }
-                            } else {
-                                feature_item == user_item
-                            }
-                        })
-                    })
-                } else {
-                    false
-                }
-            },
-            _ => false,
-        }
+    ) -> Self {
+        let items = items
+            .iter()
+            .map(|item| match item {
+                GrowthBookAttributeValue::String(it) if case_insensitive => GrowthBookAttributeValue::String(it.to_lowercase()),
+                it => it.clone(),
+            })
+            .collect();
+        Self { items, case_insensitive }
     }
+}
 
-    pub fn ne(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        _recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
+pub struct OperatorCondition;
+
+impl OperatorCondition {
+    pub fn eq(
+        operand: &EqOperand,
+        user_value: Option<&GrowthBookAttributeValue>,
     ) -> bool {
-        if let Some(user_value) = ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key) {
-            !match &user_value {
-                GrowthBookAttributeValue::Array(it) => it.iter().any(|item| item == &feature_attribute.value),
-                // inverse of `eq`: nested-object conditions resolve the parent
-                // key to the whole object and rely on the flattened-string
-                // comparison rather than structural `PartialEq`.
-                GrowthBookAttributeValue::Object(_) => user_value.to_string() == feature_attribute.value.to_string(),
-                // Scalars (and a present `null`, i.e. `Empty`) use JS `===`:
-                // `null !== 5` is true, `null !== null` is false, and `Int`/`Float`
-                // are one JS `number` type so `5 === 5.0`.
-                it => it.strict_eq(&feature_attribute.value),
-            }
-        } else {
-            true
+        match user_value {
+            Some(GrowthBookAttributeValue::Array(it)) => it.iter().any(|item| item == &operand.value),
+            // A nested-object condition like {tags: {hello: "world"}} reaches
+            // here with the parent key resolving to the whole object; the
+            // flattened-string comparison is load-bearing for that case, so
+            // keep it for objects.
+            Some(it @ GrowthBookAttributeValue::Object(_)) => it.to_string() == operand.flattened,
+            // Scalars (and a present `null`, i.e. `Empty`) use JS `===`: no
+            // string coercion (so `$eq: 5` does NOT match "5"), `null === null`
+            // but `null !== 5`, and `Int`/`Float` are one JS `number` type so
+            // `$eq: 5` matches `5.0`. Coercion stays on $lt/$gt only.
+            Some(it) => it.strict_eq(&operand.value),
+            None => false,
         }
     }
 
-    pub fn eq(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        _recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
+    /// The inverse of `eq`, except that a missing attribute passes.
+    pub fn ne(
+        operand: &EqOperand,
+        user_value: Option<&GrowthBookAttributeValue>,
     ) -> bool {
-        if let Some(user_value) = ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key) {
-            match &user_value {
-                GrowthBookAttributeValue::Array(it) => it.iter().any(|item| item == &feature_attribute.value),
-                // A nested-object condition like {tags: {hello: "world"}} reaches
-                // here via the recursive object path, with the parent key
-                // resolving to the whole object; the flattened-string comparison
-                // is load-bearing for that case, so keep it for objects.
-                GrowthBookAttributeValue::Object(_) => user_value.to_string() == feature_attribute.value.to_string(),
-                // Scalars (and a present `null`, i.e. `Empty`) use JS `===`: no
-                // string coercion (so `$eq: 5` does NOT match "5"), `null === null`
-                // but `null !== 5`, and `Int`/`Float` are one JS `number` type so
-                // `$eq: 5` matches `5.0`. Coercion stays on $lt/$gt only.
-                it => it.strict_eq(&feature_attribute.value),
-            }
-        } else {
-            false
-        }
+        user_value.is_none() || !Self::eq(operand, user_value)
     }
 
     pub fn exists(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        _recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
+        expected: bool,
+        user_value: Option<&GrowthBookAttributeValue>,
     ) -> bool {
-        if let GrowthBookAttributeValue::Bool(it) = feature_attribute.value {
-            if ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key).is_some() {
-                it
-            } else {
-                !it
-            }
-        } else {
-            true
-        }
+        user_value.is_some() == expected
     }
 
     pub fn is_in(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        case_insensitive: bool,
-        _recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
+        operand: &InOperand,
+        user_value: Option<&GrowthBookAttributeValue>,
     ) -> bool {
-        if let Some(user_value) = ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key) {
-            match &feature_attribute.value {
-                GrowthBookAttributeValue::Array(feature_array) => feature_array.iter().any(|feature_item| match &user_value {
-                    GrowthBookAttributeValue::Array(user_array) => user_array.iter().any(|user_item| {
-                        if case_insensitive {
-                            feature_item.to_string().to_lowercase() == user_item.to_string().to_lowercase()
-                        } else {
-                            feature_item.to_string() == user_item.to_string()
-                        }
-                    }),
-                    GrowthBookAttributeValue::Empty => false,
-                    it => {
-                        if case_insensitive {
-                            feature_item.to_string().to_lowercase() == it.to_string().to_lowercase()
-                        } else {
-                            feature_item.to_string() == it.to_string()
-                        }
-                    },
-                }),
-                _ => false,
-            }
-        } else {
-            false
+        match user_value {
+            Some(GrowthBookAttributeValue::Array(user_array)) => user_array.iter().any(|user_item| operand.contains(user_item)),
+            Some(GrowthBookAttributeValue::Empty) | None => false,
+            Some(it) => operand.contains(it),
         }
     }
 
+    /// Not in the list; a missing attribute fails, a `null` one passes.
     pub fn nin(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        case_insensitive: bool,
-        _recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
+        operand: &InOperand,
+        user_value: Option<&GrowthBookAttributeValue>,
     ) -> bool {
-        if let Some(user_value) = ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key) {
-            match &feature_attribute.value {
-                GrowthBookAttributeValue::Array(feature_array) => feature_array.iter().all(|feature_item| !match &user_value {
-                    GrowthBookAttributeValue::Array(user_array) => user_array.iter().any(|user_item| {
-                        if case_insensitive {
-                            feature_item.to_string().to_lowercase() == user_item.to_string().to_lowercase()
-                        } else {
-                            feature_item.to_string() == user_item.to_string()
-                        }
-                    }),
-                    GrowthBookAttributeValue::Empty => false,
-                    it => {
-                        if case_insensitive {
-                            feature_item.to_string().to_lowercase() == it.to_string().to_lowercase()
-                        } else {
-                            feature_item.to_string() == it.to_string()
-                        }
-                    },
-                }),
-                _ => false,
-            }
-        } else {
-            false
-        }
+        user_value.is_some() && !Self::is_in(operand, user_value)
     }
 
-    pub fn in_group(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
+    pub fn all(
+        operand: &AllOperand,
+        user_value: Option<&GrowthBookAttributeValue>,
     ) -> bool {
-        // The condition value is a saved-group id; look it up and test membership.
-        if let GrowthBookAttributeValue::String(group_id) = &feature_attribute.value {
-            if let Some(members) = ctx.saved_group(group_id) {
-                if let Some(user_value) = ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key) {
-                    return value_in_members(&user_value, members);
-                }
-            }
-        }
-        false
-    }
+        let Some(GrowthBookAttributeValue::Array(user_values)) = user_value else {
+            return false;
+        };
 
-    pub fn not_in_group(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-    ) -> bool {
-        // Negation: an unknown group or a missing attribute means "not in group".
-        !Self::in_group(parent_attribute, feature_attribute, ctx)
+        operand.items.iter().all(|feature_item| {
+            user_values.iter().any(|user_item| match (feature_item, user_item) {
+                (GrowthBookAttributeValue::String(f), GrowthBookAttributeValue::String(u)) if operand.case_insensitive => *f == u.to_lowercase(),
+                _ => feature_item == user_item,
+            })
+        })
     }
 
-    pub fn or(
-        _parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
+    pub fn in_group(
+        group_id: &str,
+        user_value: Option<&GrowthBookAttributeValue>,
         ctx: &ConditionEvalContext,
-        recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
     ) -> bool {
-        match &feature_attribute.value {
-            GrowthBookAttributeValue::Array(it) => {
-                if it.is_empty() {
-                    true
-                } else {
-                    it.iter().any(|next_value| match next_value {
-                        GrowthBookAttributeValue::Object(feature_value) => feature_value.iter().all(|next_attribute| recursive(None, next_attribute, ctx, false)),
-                        _ => false,
-                    })
-                }
-            },
-            GrowthBookAttributeValue::Empty => true,
+        // The condition value is a saved-group id; look it up and test membership.
+        match (ctx.saved_group(group_id), user_value) {
+            (Some(members), Some(user_value)) => value_in_members(user_value, members),
             _ => false,
         }
     }
@@ -256,25 +175,3 @@ fn value_in_members(
         other => members.contains(other),
     }
 }
-
-fn and_nor(
-    feature_attribute: &&GrowthBookAttribute,
-    ctx: &ConditionEvalContext,
-    recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
-    negate: bool,
-) -> bool {
-    match &feature_attribute.value {
-        GrowthBookAttributeValue::Array(it) => it.iter().all(|next_value| match next_value {
-            GrowthBookAttributeValue::Object(feature_value) => {
-                let result = feature_value.iter().all(|next_attribute| recursive(None, next_attribute, ctx, false));
-                if negate {
-                    !result
-                } else {
-                    result
-                }
-            },
-            _ => false,
-        }),
-        _ => false,
-    }
-}
@@ -1,127 +1,74 @@
-use crate::condition::eval_context::ConditionEvalContext;
-use crate::extensions::FindGrowthBookAttribute;
-use crate::model_public::{GrowthBookAttribute, GrowthBookAttributeValue};
+use crate::model_public::GrowthBookAttributeValue;
 
-pub struct OrderComparison;
-
-impl OrderComparison {
-    pub fn gt(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        array_size: bool,
-    ) -> bool {
-        evaluate(
-            parent_attribute,
-            feature_attribute,
-            ctx,
-            array_size,
-            |feature_number, user_number| user_number.gt(feature_number),
-            |feature_string, user_string| user_string.gt(feature_string),
-        )
-    }
-
-    pub fn gte(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        array_size: bool,
-    ) -> bool {
-        evaluate(
-            parent_attribute,
-            feature_attribute,
-            ctx,
-            array_size,
-            |feature_number, user_number| user_number.ge(feature_number),
-            |feature_string, user_string| user_string.ge(feature_string),
-        )
-    }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderOperator {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
 
-    pub fn lt(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        array_size: bool,
+impl OrderOperator {
+    fn compare<T: PartialOrd + ?Sized>(
+        self,
+        user_value: &T,
+        feature_value: &T,
     ) -> bool {
-        evaluate(
-            parent_attribute,
-            feature_attribute,
-            ctx,
-            array_size,
-            |feature_number, user_number| user_number.lt(feature_number),
-            |feature_string, user_string| user_string.lt(feature_string),
-        )
-    }
-
-    pub fn lte(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        array_size: bool,
-    ) -> bool {
-        evaluate(
-            parent_attribute,
-            feature_attribute,
-            ctx,
-            array_size,
-            |feature_number, user_number| user_number.le(feature_number),
-            |feature_string, user_string| user_string.le(feature_string),
-        )
+        match self {
+            OrderOperator::Gt => user_value > feature_value,
+            OrderOperator::Gte => user_value >= feature_value,
+            OrderOperator::Lt => user_value < feature_value,
+            OrderOperator::Lte => user_value <= feature_value,
+        }
     }
 }
 
-fn evaluate(
-    parent_attribute: Option<&GrowthBookAttribute>,
-    feature_attribute: &GrowthBookAttribute,
-    ctx: &ConditionEvalContext,
-    array_size: bool,
-    string_condition: fn(&str, &str) -> bool,
-    number_condition: fn(&f64, &f64) -> bool,
-) -> bool {
-    if feature_attribute.value.is_number() {
-        number_evaluate(parent_attribute, feature_attribute, ctx, array_size, number_condition)
-    } else {
-        string_evaluate(parent_attribute, feature_attribute, ctx, string_condition)
-    }
+/// The right-hand side of `$gt` and friends: numeric-looking operands
+/// compare as numbers, everything else as strings.
+#[derive(Clone, Debug)]
+pub enum OrderOperand {
+    Number(Option<f64>),
+    String(String),
 }
 
-fn string_evaluate(
-    parent_attribute: Option<&GrowthBookAttribute>,
-    feature_attribute: &GrowthBookAttribute,
-    ctx: &ConditionEvalContext,
-    condition: fn(&str, &str) -> bool,
-) -> bool {
-    if let Some(user_value) = ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key) {
-        let feature_value = feature_attribute.value.to_string();
-        match user_value {
-            GrowthBookAttributeValue::Array(it) => it.iter().any(|item| condition(&feature_value, &item.to_string())),
-            it => condition(&feature_value, &it.to_string()),
+pub struct OrderComparison;
+
+impl OrderComparison {
+    pub fn compile(feature_value: &GrowthBookAttributeValue) -> OrderOperand {
+        if feature_value.is_number() {
+            OrderOperand::Number(get_feature_number(feature_value))
+        } else {
+            OrderOperand::String(feature_value.to_string())
         }
-    } else {
-        true
     }
-}
 
-fn number_evaluate(
-    parent_attribute: Option<&GrowthBookAttribute>,
-    feature_attribute: &GrowthBookAttribute,
-    ctx: &ConditionEvalContext,
-    array_size: bool,
-    condition: fn(&f64, &f64) -> bool,
-) -> bool {
-    if let Some(user_value) = ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key) {
-        if let (Some(feature_number), Some(user_numbers)) = (get_feature_number(feature_attribute), get_user_numbers(&user_value, array_size)) {
-            user_numbers.iter().any(|number| condition(&feature_number, number))
-        } else {
-            false
+    /// A missing attribute passes. With `array_size`, an array compares by
+    /// its length (`$size: {$gt: ...}`).
+    pub fn matches(
+        operator: OrderOperator,
+        operand: &OrderOperand,
+        user_value: Option<&GrowthBookAttributeValue>,
+        array_size: bool,
+    ) -> bool {
+        let Some(user_value) = user_value else {
+            return true;
+        };
+
+        match operand {
+            OrderOperand::Number(feature_number) => match (feature_number, get_user_numbers(user_value, array_size)) {
+                (Some(feature_number), Some(user_numbers)) => user_numbers.iter().any(|number| operator.compare(number, feature_number)),
+                _ => false,
+            },
+            OrderOperand::String(feature_string) => match user_value {
+                GrowthBookAttributeValue::Array(it) => it.iter().any(|item| operator.compare(item.to_string().as_str(), feature_string)),
+                it => operator.compare(it.to_string().as_str(), feature_string),
+            },
         }
-    } else {
-        true
     }
 }
 
-fn get_feature_number(feature_attribute: &GrowthBookAttribute) -> Option<f64> {
-    match &feature_attribute.value {
+fn get_feature_number(feature_value: &GrowthBookAttributeValue) -> Option<f64> {
+    match feature_value {
         GrowthBookAttributeValue::Int(it) => Some(*it as f64),
         GrowthBookAttributeValue::Float(it) => Some(*it),
         GrowthBookAttributeValue::String(string_number) => string_number.replace('.', "").parse::<f64>().ok(),
@@ -140,7 +87,7 @@ fn get_user_numbers(
             if array_size {
                 Some(vec![it.len() as f64])
             } else {
-                Some(it.iter().filter(|item| item.is_number()).map(|item| item.as_f64().expect("Failed to convert to f64")).collect())
+                Some(it.iter().filter(|item| item.is_number()).filter_map(|item| item.as_f64()).collect())
             }
         },
         GrowthBookAttributeValue::String(string_number) => string_number.replace('.', "").parse::<f64>().ok().map(|it| vec![it]),
@@ -1,26 +1,18 @@
-use crate::condition::eval_context::ConditionEvalContext;
-use crate::extensions::FindGrowthBookAttribute;
-use crate::model_public::{GrowthBookAttribute, GrowthBookAttributeValue};
+use crate::model_public::GrowthBookAttributeValue;
 
 pub struct SizeComparison;
 
 impl SizeComparison {
+    /// `$size: n`; a `$size` holding a condition compares the array's length
+    /// through the order operators instead.
     pub fn matches(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-        recursive: fn(Option<&GrowthBookAttribute>, &GrowthBookAttribute, &ConditionEvalContext, bool) -> bool,
+        size: i64,
+        user_value: Option<&GrowthBookAttributeValue>,
     ) -> bool {
-        match &feature_attribute.value {
-            GrowthBookAttributeValue::Int(feature_value) => {
-                if let Some(GrowthBookAttributeValue::Array(user_value)) = ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key) {
-                    feature_value == &(user_value.len() as i64)
-                } else {
-                    false
-                }
-            },
-            GrowthBookAttributeValue::Object(feature_value) => feature_value.iter().all(|next| recursive(parent_attribute, next, ctx, true)),
-            _ => false,
+        if let Some(GrowthBookAttributeValue::Array(user_value)) = user_value {
+            size == user_value.len() as i64
+        } else {
+            false
         }
     }
 }
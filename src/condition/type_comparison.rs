@@ -1,32 +1,21 @@
-use crate::condition::eval_context::ConditionEvalContext;
-use crate::extensions::FindGrowthBookAttribute;
-use crate::model_public::{GrowthBookAttribute, GrowthBookAttributeValue};
+use crate::model_public::GrowthBookAttributeValue;
 
 pub struct TypeComparison;
 
 impl TypeComparison {
     pub fn matches(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
+        feature_type: &str,
+        user_value: Option<&GrowthBookAttributeValue>,
     ) -> bool {
-        if let GrowthBookAttributeValue::String(feature_type) = &feature_attribute.value {
-            if let Some(user_value) = ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key) {
-                match user_value {
-                    GrowthBookAttributeValue::String(_) => feature_type == "string",
-                    GrowthBookAttributeValue::Int(_) => feature_type == "number",
-                    GrowthBookAttributeValue::Float(_) => feature_type == "number",
-                    GrowthBookAttributeValue::Bool(_) => feature_type == "boolean",
-                    GrowthBookAttributeValue::Array(_) => feature_type == "array",
-                    // Any object, including the empty object `{}`, is "object".
-                    GrowthBookAttributeValue::Object(_) => feature_type == "object",
-                    GrowthBookAttributeValue::Empty => feature_type == "null",
-                }
-            } else {
-                feature_type == "null"
-            }
-        } else {
-            false
+        match user_value {
+            Some(GrowthBookAttributeValue::String(_)) => feature_type == "string",
+            Some(GrowthBookAttributeValue::Int(_)) => feature_type == "number",
+            Some(GrowthBookAttributeValue::Float(_)) => feature_type == "number",
+            Some(GrowthBookAttributeValue::Bool(_)) => feature_type == "boolean",
+            Some(GrowthBookAttributeValue::Array(_)) => feature_type == "array",
+            // Any object, including the empty object `{}`, is "object".
+            Some(GrowthBookAttributeValue::Object(_)) => feature_type == "object",
+            Some(GrowthBookAttributeValue::Empty) | None => feature_type == "null",
         }
     }
 }
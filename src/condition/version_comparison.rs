@@ -1,97 +1,66 @@
-use crate::condition::eval_context::ConditionEvalContext;
+use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::extensions::FindGrowthBookAttribute;
-use crate::model_public::{GrowthBookAttribute, GrowthBookAttributeValue};
+use crate::model_public::GrowthBookAttributeValue;
 
-pub struct VersionComparison;
-
-impl VersionComparison {
-    pub fn vgt(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-    ) -> bool {
-        evaluate(parent_attribute, feature_attribute, ctx, |feature_version, user_version| user_version.gt(feature_version))
-    }
-
-    pub fn vgte(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-    ) -> bool {
-        evaluate(parent_attribute, feature_attribute, ctx, |feature_version, user_version| user_version.ge(feature_version))
-    }
+lazy_static! {
+    static ref PREFIX_AND_BUILD: Regex = Regex::new("(^v|\\+.*$)").expect("valid regex");
+    static ref SEPARATORS: Regex = Regex::new("[-.]").expect("valid regex");
+    static ref NUMERIC_PART: Regex = Regex::new("^\\d+").expect("valid regex");
+}
 
-    pub fn vlt(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-    ) -> bool {
-        evaluate(parent_attribute, feature_attribute, ctx, |feature_version, user_version| user_version.lt(feature_version))
-    }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionOperator {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Eq,
+    Ne,
+}
 
-    pub fn vlte(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-    ) -> bool {
-        evaluate(parent_attribute, feature_attribute, ctx, |feature_version, user_version| user_version.le(feature_version))
-    }
+pub struct VersionComparison;
 
-    pub fn veq(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
-    ) -> bool {
-        evaluate(parent_attribute, feature_attribute, ctx, |feature_version, user_version| user_version.eq(feature_version))
+impl VersionComparison {
+    /// The condition's version, normalized once so that comparisons only
+    /// normalize the user's.
+    pub fn compile(feature_value: &GrowthBookAttributeValue) -> String {
+        normalize(&feature_value.to_string())
     }
 
-    pub fn vne(
-        parent_attribute: Option<&GrowthBookAttribute>,
-        feature_attribute: &GrowthBookAttribute,
-        ctx: &ConditionEvalContext,
+    /// Anything but a string version passes, as a missing attribute does.
+    pub fn matches(
+        operator: VersionOperator,
+        feature_version: &str,
+        user_value: Option<&GrowthBookAttributeValue>,
     ) -> bool {
-        evaluate(parent_attribute, feature_attribute, ctx, |feature_version, user_version| user_version.ne(feature_version))
-    }
-}
+        let Some(GrowthBookAttributeValue::String(user_version)) = user_value else {
+            return true;
+        };
 
-fn evaluate(
-    parent_attribute: Option<&GrowthBookAttribute>,
-    feature_attribute: &GrowthBookAttribute,
-    ctx: &ConditionEvalContext,
-    condition: fn(&str, &str) -> bool,
-) -> bool {
-    if let Some(GrowthBookAttributeValue::String(user_version)) = ctx.find_value(&parent_attribute.unwrap_or(feature_attribute).key) {
-        let feature_version = feature_attribute.value.to_string();
-        condition(&normalize(&feature_version), &normalize(&user_version))
-    } else {
-        true
+        let user_version = normalize(user_version);
+        let user_version = user_version.as_str();
+        match operator {
+            VersionOperator::Gt => user_version > feature_version,
+            VersionOperator::Gte => user_version >= feature_version,
+            VersionOperator::Lt => user_version < feature_version,
+            VersionOperator::Lte => user_version <= feature_version,
+            VersionOperator::Eq => user_version == feature_version,
+            VersionOperator::Ne => user_version != feature_version,
+        }
     }
 }
 
 fn normalize(version: &str) -> String {
-    if let Ok(regex1) = Regex::new("(^v|\\+.*$)") {
-        if let Ok(regex2) = Regex::new("[-.]") {
-            if let Ok(regex3) = Regex::new("^\\d+") {
-                let string = regex1.replace_all(version, "").to_string();
-                let mut split = regex2.split(&string).filter(|item| !item.is_empty()).collect::<Vec<&str>>();
-                if split.len() == 3 {
-                    split.push("~");
-                }
-                split
-                    .iter()
-                    .map(|part| if regex3.is_match(part) { format!("{:0>5}", part) } else { part.to_string() })
-                    .filter(|part| !part.is_empty())
-                    .reduce(|a, b| format!("{a}-{b}"))
-                    .unwrap_or(version.to_string())
-            } else {
-                version.to_string()
-            }
-        } else {
-            version.to_string()
-        }
-    } else {
-        version.to_string()
+    let string = PREFIX_AND_BUILD.replace_all(version, "").to_string();
+    let mut split = SEPARATORS.split(&string).filter(|item| !item.is_empty()).collect::<Vec<&str>>();
+    if split.len() == 3 {
+        split.push("~");
     }
+    split
+        .iter()
+        .map(|part| if NUMERIC_PART.is_match(part) { format!("{:0>5}", part) } else { part.to_string() })
+        .filter(|part| !part.is_empty())
+        .reduce(|a, b| format!("{a}-{b}"))
+        .unwrap_or(version.to_string())
 }
@@ -15,24 +15,32 @@ impl Coverage {
         hash_version: Option<i64>,
         force_value: Value,
     ) -> Option<FeatureResult> {
-        if let Some(user_weight) = HashCode::hash_code(&value.to_string(), feature_name, HashCodeVersion::from(hash_version)) {
-            if let Some(range) = option_range {
-                if range.in_range(&user_weight) {
-                    Some(FeatureResult::force(force_value.clone()))
-                } else {
-                    None
-                }
-            } else if let Some(coverage) = option_coverage {
-                if coverage.gt(&user_weight) {
-                    Some(FeatureResult::force(force_value.clone()))
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+        let user_weight = Self::bucket(value, feature_name, hash_version)?;
+        Self::includes(user_weight, option_coverage, option_range).then(|| FeatureResult::force(force_value))
+    }
+
+    /// The user's hashed weight for `seed`.
+    pub fn bucket(
+        value: &GrowthBookAttributeValue,
+        seed: &str,
+        hash_version: Option<i64>,
+    ) -> Option<f32> {
+        HashCode::hash_code(&value.to_string(), seed, HashCodeVersion::from(hash_version))
+    }
+
+    /// Whether the weight falls in the range or, without one, under the
+    /// coverage. Neither means nobody is included.
+    pub fn includes(
+        user_weight: f32,
+        option_coverage: Option<f32>,
+        option_range: Option<Range>,
+    ) -> bool {
+        if let Some(range) = option_range {
+            range.in_range(&user_weight)
+        } else if let Some(coverage) = option_coverage {
+            coverage.gt(&user_weight)
         } else {
-            None
+            false
         }
     }
 }
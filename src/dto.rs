@@ -1,26 +1,85 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::extensions::JsonHelper;
-use crate::model_public::{Experiment, GrowthBookAttribute, GrowthBookAttributeValue};
+use crate::model_public::{Experiment, GrowthBookAttribute, GrowthBookAttributeValue, RuleTraceKind, TrackData};
+use crate::namespace::use_case::Namespace;
 use crate::range::model::Range;
 
-#[derive(Deserialize, Clone, Default)]
+pub use crate::condition::compiled::{Condition, ConditionError};
+
+#[derive(Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GrowthBookResponse {
     pub forced_variations: Option<HashMap<String, i64>>,
     pub features: Option<HashMap<String, GrowthBookFeature>>,
     pub encrypted_features: Option<String>,
     pub saved_groups: Option<Value>,
+    /// Auto experiments (e.g. URL redirects) that run outside of features.
+    pub experiments: Option<Vec<Experiment>>,
+    pub encrypted_experiments: Option<String>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+/// Body of a remote evaluation request (`POST /api/eval/{clientKey}`), as
+/// sent by the JS SDK. Maps are ordered so equal requests serialize equally.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteEvalRequest {
+    pub attributes: BTreeMap<String, Value>,
+    pub forced_variations: BTreeMap<String, i64>,
+    pub forced_features: Vec<(String, Value)>,
+    pub url: String,
+}
+
+impl RemoteEvalRequest {
+    pub fn new(
+        attributes: &[GrowthBookAttribute],
+        forced_variations: &HashMap<String, i64>,
+        forced_features: &HashMap<String, Value>,
+    ) -> Self {
+        let attributes: BTreeMap<String, Value> = attributes.iter().map(|attribute| (attribute.key.clone(), attribute.value.to_value())).collect();
+        let url = attributes.get("url").and_then(Value::as_str).unwrap_or_default().to_string();
+        let mut forced_features: Vec<(String, Value)> = forced_features.iter().map(|(key, value)| (key.clone(), value.clone())).collect();
+        forced_features.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Self {
+            attributes,
+            forced_variations: forced_variations.iter().map(|(key, variation)| (key.clone(), *variation)).collect(),
+            forced_features,
+            url,
+        }
+    }
+
+    /// Cache key for the payload evaluated for this request.
+    pub fn cache_key(&self) -> String {
+        format!("remote:{}", serde_json::to_string(self).unwrap_or_default())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GrowthBookFeature {
     pub default_value: Option<Value>,
     pub rules: Option<Vec<GrowthBookFeatureRule>>,
+    pub holdout: Option<GrowthBookFeatureHoldout>,
+}
+
+/// Puts a feature in a holdout. `id` names the holdout's own feature in the
+/// payload, whose experiment rule is a holdout group as in the conformance
+/// suite's "Support holdout groups" case: the measured population's variation
+/// is marked `passthrough` in `meta`, and users assigned any other variation
+/// are held out.
+///
+/// The reference itself isn't in the conformance suite, which only has the
+/// holdout group; `tests/holdout.rs` runs that case both inline and through
+/// this reference.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GrowthBookFeatureHoldout {
+    pub id: String,
+    /// Served to held-out users; the feature's `defaultValue` when absent.
+    pub value: Option<Value>,
 }
 
 // A feature rule is deserialized into the flat `GrowthBookFeatureRuleDto`
@@ -28,22 +87,42 @@ pub struct GrowthBookFeature {
 // `filters` can never be silently dropped), then normalized exactly once into
 // this struct. `parent_conditions` is common to every rule kind; `kind` is a
 // zero-cost enum the evaluation hot path matches on without probing options.
-#[derive(Deserialize, Clone, Debug)]
-#[serde(from = "GrowthBookFeatureRuleDto")]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(from = "GrowthBookFeatureRuleDto", into = "GrowthBookFeatureRuleDto")]
 pub struct GrowthBookFeatureRule {
+    pub id: Option<String>,
     pub parent_conditions: Option<Vec<GrowthBookFeatureRuleParentData>>,
     pub kind: GrowthBookFeatureRuleKind,
 }
 
 impl GrowthBookFeatureRule {
     /// The rule's `filters`, regardless of kind. JS evaluates `rule.filters`
-    /// once in the rule loop (core.ts) for every rule type. `Rollout` rules
-    /// don't currently carry filters (see the rollout-filters follow-up).
+    /// once in the rule loop (core.ts) for every rule type.
     pub fn filters(&self) -> Option<&Value> {
         match &self.kind {
             GrowthBookFeatureRuleKind::Force(it) => it.filters.as_ref(),
             GrowthBookFeatureRuleKind::Experiment(it) => it.filters.as_ref(),
-            GrowthBookFeatureRuleKind::Rollout(_) | GrowthBookFeatureRuleKind::Empty => None,
+            GrowthBookFeatureRuleKind::Rollout(it) => it.filters.as_ref(),
+            GrowthBookFeatureRuleKind::Empty => None,
+        }
+    }
+
+    /// The rule's targeting condition, regardless of kind.
+    pub fn condition(&self) -> Option<&Condition> {
+        match &self.kind {
+            GrowthBookFeatureRuleKind::Force(it) => it.condition(),
+            GrowthBookFeatureRuleKind::Experiment(it) => it.condition(),
+            GrowthBookFeatureRuleKind::Rollout(it) => it.condition(),
+            GrowthBookFeatureRuleKind::Empty => None,
+        }
+    }
+
+    pub fn trace_kind(&self) -> RuleTraceKind {
+        match &self.kind {
+            GrowthBookFeatureRuleKind::Force(_) => RuleTraceKind::Force,
+            GrowthBookFeatureRuleKind::Experiment(_) => RuleTraceKind::Experiment,
+            GrowthBookFeatureRuleKind::Rollout(_) => RuleTraceKind::Rollout,
+            GrowthBookFeatureRuleKind::Empty => RuleTraceKind::Empty,
         }
     }
 }
@@ -59,9 +138,10 @@ pub enum GrowthBookFeatureRuleKind {
     Empty,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GrowthBookFeatureRuleDto {
+    id: Option<String>,
     parent_conditions: Option<Vec<GrowthBookFeatureRuleParentData>>,
     condition: Option<Value>,
     filters: Option<Value>,
@@ -82,11 +162,13 @@ pub struct GrowthBookFeatureRuleDto {
     bucket_version: Option<i64>,
     min_bucket_version: Option<i64>,
     disable_sticky_bucketing: Option<bool>,
+    tracks: Option<Vec<TrackData>>,
 }
 
 impl From<GrowthBookFeatureRuleDto> for GrowthBookFeatureRule {
     fn from(dto: GrowthBookFeatureRuleDto) -> Self {
         let GrowthBookFeatureRuleDto {
+            id,
             parent_conditions,
             condition,
             filters,
@@ -107,6 +189,7 @@ impl From<GrowthBookFeatureRuleDto> for GrowthBookFeatureRule {
             bucket_version,
             min_bucket_version,
             disable_sticky_bucketing,
+            tracks,
         } = dto;
 
         // Classification preserves the precedence of the former untagged enum
@@ -126,7 +209,7 @@ impl From<GrowthBookFeatureRuleDto> for GrowthBookFeatureRule {
                 ranges,
                 meta,
                 filters,
-                condition: value_to_condition_map(condition),
+                condition: condition.map(Condition::compile),
                 bucket_version,
                 min_bucket_version,
                 disable_sticky_bucketing,
@@ -137,10 +220,13 @@ impl From<GrowthBookFeatureRuleDto> for GrowthBookFeatureRule {
                     force,
                     coverage,
                     range,
-                    condition: value_to_condition_map(condition),
+                    condition: condition.map(Condition::compile),
                     hash_attribute,
                     fallback_attribute,
                     hash_version,
+                    filters,
+                    seed,
+                    tracks: tracks.unwrap_or_default(),
                 })
             } else {
                 GrowthBookFeatureRuleKind::Force(GrowthBookFeatureRuleForce {
@@ -150,21 +236,83 @@ impl From<GrowthBookFeatureRuleDto> for GrowthBookFeatureRule {
                     hash_version,
                     filters,
                     seed,
-                    condition: value_to_condition_map(condition),
+                    condition: condition.map(Condition::compile),
                     hash_attribute,
                     fallback_attribute,
+                    tracks: tracks.unwrap_or_default(),
                 })
             }
         } else {
             GrowthBookFeatureRuleKind::Empty
         };
 
-        GrowthBookFeatureRule { parent_conditions, kind }
+        GrowthBookFeatureRule { id, parent_conditions, kind }
     }
 }
 
-fn value_to_condition_map(condition: Option<Value>) -> Option<HashMap<String, Value>> {
-    condition.and_then(|value| value.as_object().map(|map| map.clone().into_iter().collect()))
+// The reverse of the classification above, so cached payloads serialize to
+// the wire format they were parsed from.
+impl From<GrowthBookFeatureRule> for GrowthBookFeatureRuleDto {
+    fn from(rule: GrowthBookFeatureRule) -> Self {
+        let GrowthBookFeatureRule { id, parent_conditions, kind } = rule;
+        let dto = GrowthBookFeatureRuleDto {
+            id,
+            parent_conditions,
+            ..Default::default()
+        };
+
+        match kind {
+            GrowthBookFeatureRuleKind::Experiment(experiment) => {
+                let experiment = *experiment;
+                GrowthBookFeatureRuleDto {
+                    key: experiment.key,
+                    variations: Some(experiment.variations),
+                    name: experiment.name,
+                    coverage: experiment.coverage,
+                    seed: experiment.seed,
+                    hash_version: experiment.hash_version,
+                    hash_attribute: experiment.hash_attribute,
+                    fallback_attribute: experiment.fallback_attribute,
+                    weights: experiment.weights,
+                    namespace: experiment.namespace,
+                    ranges: experiment.ranges,
+                    meta: experiment.meta,
+                    filters: experiment.filters,
+                    condition: experiment.condition.map(|it| it.source().clone()),
+                    bucket_version: experiment.bucket_version,
+                    min_bucket_version: experiment.min_bucket_version,
+                    disable_sticky_bucketing: experiment.disable_sticky_bucketing,
+                    ..dto
+                }
+            },
+            GrowthBookFeatureRuleKind::Rollout(rollout) => GrowthBookFeatureRuleDto {
+                force: Some(rollout.force),
+                coverage: Some(rollout.coverage),
+                range: rollout.range,
+                condition: rollout.condition.map(|it| it.source().clone()),
+                hash_attribute: rollout.hash_attribute,
+                fallback_attribute: rollout.fallback_attribute,
+                hash_version: rollout.hash_version,
+                filters: rollout.filters,
+                seed: rollout.seed,
+                tracks: Some(rollout.tracks),
+                ..dto
+            },
+            GrowthBookFeatureRuleKind::Force(force) => GrowthBookFeatureRuleDto {
+                force: Some(force.force),
+                range: force.range,
+                hash_version: force.hash_version,
+                filters: force.filters,
+                seed: force.seed,
+                condition: force.condition.map(|it| it.source().clone()),
+                hash_attribute: force.hash_attribute,
+                fallback_attribute: force.fallback_attribute,
+                tracks: Some(force.tracks),
+                ..dto
+            },
+            GrowthBookFeatureRuleKind::Empty => dto,
+        }
+    }
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -176,16 +324,18 @@ pub struct GrowthBookFeatureRuleForce {
     pub hash_version: Option<i64>,
     pub filters: Option<Value>,
     pub seed: Option<String>,
-    condition: Option<HashMap<String, Value>>,
+    condition: Option<Condition>,
     pub hash_attribute: Option<String>,
     pub fallback_attribute: Option<String>,
+    #[serde(default)]
+    pub tracks: Vec<TrackData>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GrowthBookFeatureRuleParentData {
     pub id: String,
-    condition: Option<HashMap<String, Value>>,
+    condition: Option<Condition>,
     #[serde(default)]
     pub gate: bool,
 }
@@ -196,10 +346,14 @@ pub struct GrowthBookFeatureRuleRollout {
     pub force: Value,
     pub coverage: f32,
     range: Option<Vec<f32>>,
-    condition: Option<HashMap<String, Value>>,
+    condition: Option<Condition>,
     pub hash_attribute: Option<String>,
     pub fallback_attribute: Option<String>,
     pub hash_version: Option<i64>,
+    pub filters: Option<Value>,
+    pub seed: Option<String>,
+    #[serde(default)]
+    pub tracks: Vec<TrackData>,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -218,21 +372,35 @@ pub struct GrowthBookFeatureRuleExperiment {
     pub ranges: Option<Vec<Vec<f32>>>,
     pub meta: Option<Value>,
     pub filters: Option<Value>,
-    pub condition: Option<HashMap<String, Value>>,
+    pub condition: Option<Condition>,
     pub bucket_version: Option<i64>,
     pub min_bucket_version: Option<i64>,
     pub disable_sticky_bucketing: Option<bool>,
 }
 
 impl GrowthBookFeatureRuleParentData {
+    pub fn condition(&self) -> Option<&Condition> {
+        self.condition.as_ref()
+    }
+
+    /// The condition's top-level entries as attributes, from its source
+    /// JSON.
+    #[deprecated(note = "use `condition()`, which is compiled once when the payload loads")]
     pub fn conditions(&self) -> Option<Vec<GrowthBookAttribute>> {
-        option_map_to_attributes(self.condition.clone())
+        option_map_to_attributes(source_entries(&self.condition))
     }
 }
 
 impl GrowthBookFeatureRuleRollout {
+    pub fn condition(&self) -> Option<&Condition> {
+        self.condition.as_ref()
+    }
+
+    /// The condition's top-level entries as attributes, from its source
+    /// JSON.
+    #[deprecated(note = "use `condition()`, which is compiled once when the payload loads")]
     pub fn conditions(&self) -> Option<Vec<GrowthBookAttribute>> {
-        option_map_to_attributes(self.condition.clone())
+        option_map_to_attributes(source_entries(&self.condition))
     }
 
     pub fn range(&self) -> Option<Range> {
@@ -245,8 +413,15 @@ impl GrowthBookFeatureRuleRollout {
 }
 
 impl GrowthBookFeatureRuleForce {
+    pub fn condition(&self) -> Option<&Condition> {
+        self.condition.as_ref()
+    }
+
+    /// The condition's top-level entries as attributes, from its source
+    /// JSON.
+    #[deprecated(note = "use `condition()`, which is compiled once when the payload loads")]
     pub fn conditions(&self) -> Option<Vec<GrowthBookAttribute>> {
-        option_map_to_attributes(self.condition.clone())
+        option_map_to_attributes(source_entries(&self.condition))
     }
 
     pub fn range(&self) -> Option<Range> {
@@ -259,8 +434,15 @@ impl GrowthBookFeatureRuleForce {
 }
 
 impl GrowthBookFeatureRuleExperiment {
+    pub fn condition(&self) -> Option<&Condition> {
+        self.condition.as_ref()
+    }
+
+    /// The condition's top-level entries as attributes, from its source
+    /// JSON.
+    #[deprecated(note = "use `condition()`, which is compiled once when the payload loads")]
     pub fn conditions(&self) -> Option<Vec<GrowthBookAttribute>> {
-        option_map_to_attributes(self.condition.clone())
+        option_map_to_attributes(source_entries(&self.condition))
     }
 
     pub fn seed(
@@ -271,47 +453,24 @@ impl GrowthBookFeatureRuleExperiment {
     }
 
     pub fn ranges(&self) -> Vec<Range> {
-        if let Some(ranges) = self.ranges.clone() {
-            // #18: a malformed tuple with fewer than two elements must not panic.
-            // A degenerate range (start >= end) matches nobody, mirroring JS where
-            // `n < undefined` is false.
-            ranges
-                .iter()
-                .map(|range| Range {
-                    start: range.first().copied().unwrap_or(0.0),
-                    end: range.get(1).copied().unwrap_or(0.0),
-                })
-                .collect()
-        } else {
-            Range::get_bucket_range(self.variations.len() as i64, &self.coverage, self.weights.clone())
-        }
+        Range::get_ranges(self.ranges.as_ref(), self.variations.len() as i64, &self.coverage, self.weights.clone())
     }
 
     pub fn namespace_range(&self) -> Option<(String, Range)> {
-        self.namespace.as_ref().map(|namespace| {
-            // #18: a malformed namespace tuple (fewer than 3 elements) must not
-            // panic. JS `inNamespace` compares the hash against `undefined` and
-            // returns false, so the user is excluded — represent that as an empty
-            // range (start >= end) that `Range::in_range` never matches.
-            match (namespace.first(), namespace.get(1), namespace.get(2)) {
-                (Some(id), Some(start), Some(end)) => (
-                    id.force_string(""),
-                    Range {
-                        start: start.force_f32(0.0),
-                        end: end.force_f32(1.0),
-                    },
-                ),
-                _ => (String::new(), Range { start: 1.0, end: 0.0 }),
-            }
-        })
+        Namespace::get_range(self.namespace.as_ref())
     }
 
-    pub fn model_experiment(&self) -> Experiment {
+    pub fn model_experiment(
+        &self,
+        feature_name: &str,
+    ) -> Experiment {
         Experiment {
+            key: self.key.clone().unwrap_or(feature_name.to_string()),
             name: self.name.clone(),
             seed: self.seed.clone(),
             hash_version: self.hash_version,
             hash_attribute: self.hash_attribute.clone(),
+            fallback_attribute: self.fallback_attribute.clone(),
             namespace: self.namespace.clone(),
             coverage: self.coverage,
             ranges: self.ranges.clone(),
@@ -319,7 +478,8 @@ impl GrowthBookFeatureRuleExperiment {
             filters: self.filters.clone(),
             variations: self.variations.clone(),
             weights: self.weights.clone(),
-            condition: self.condition.clone().map(|map| Value::Object(map.into_iter().collect())),
+            condition: self.condition.clone(),
+            ..Default::default()
         }
     }
 }
@@ -327,3 +487,13 @@ impl GrowthBookFeatureRuleExperiment {
 pub fn option_map_to_attributes(option_map: Option<HashMap<String, Value>>) -> Option<Vec<GrowthBookAttribute>> {
     option_map.map(|conditions| conditions.iter().map(|(k, v)| GrowthBookAttribute::new(k.clone(), GrowthBookAttributeValue::from(v.clone()))).collect())
 }
+
+// A condition's source JSON as a map, as the rules held it before conditions
+// were compiled.
+fn source_entries(condition: &Option<Condition>) -> Option<HashMap<String, Value>> {
+    condition
+        .as_ref()?
+        .source()
+        .as_object()
+        .map(|entries| entries.iter().map(|(key, value)| (key.clone(), value.clone())).collect())
+}
@@ -6,7 +6,7 @@ use std::num::ParseIntError;
 use chrono::OutOfRangeError;
 use reqwest::Response;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum GrowthbookErrorCode {
     GenericError,
     SerdeDeserialize,
@@ -18,9 +18,10 @@ pub enum GrowthbookErrorCode {
     InvalidResponseValueType,
     GrowthBookAttributeIsNotObject,
     ConfigError,
+    StickyBucketStorage,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GrowthbookError {
     pub code: GrowthbookErrorCode,
     pub message: String,
@@ -104,6 +105,16 @@ impl From<serde_json::Error> for GrowthbookError {
     }
 }
 
+#[cfg(feature = "sticky-bucket-sqlite")]
+impl From<rusqlite::Error> for GrowthbookError {
+    fn from(error: rusqlite::Error) -> Self {
+        Self {
+            code: GrowthbookErrorCode::StickyBucketStorage,
+            message: error.to_string(),
+        }
+    }
+}
+
 impl From<OutOfRangeError> for GrowthbookError {
     fn from(error: OutOfRangeError) -> Self {
         Self {
@@ -0,0 +1,326 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::Value;
+use url::Url;
+
+use crate::extensions::FindGrowthBookAttribute;
+use crate::model_public::{FeatureResult, GrowthBookAttributeValue, RuleOutcome, RuleTrace, RuleTraceKind, UserAttributes};
+
+/// Context-level inputs to experiment evaluation that don't come from the
+/// experiment itself. Bundled like `ConditionEvalContext` so new switches can
+/// be added without re-threading every rule signature. It also collects the
+/// rule trace for `explain_feature` and can share feature results between
+/// the features of one `evaluate_all`.
+#[derive(Debug)]
+pub struct ExperimentEvalContext {
+    url: Option<Url>,
+    query_string_overrides: bool,
+    enabled: bool,
+    qa_mode: bool,
+    forced_features: Arc<HashMap<String, Value>>,
+    trace: Option<RefCell<TraceRecorder>>,
+    feature_results: Option<RefCell<HashMap<String, FeatureResult>>>,
+    cycles: Cell<usize>,
+}
+
+// Rules open and close as a stack because evaluating a prerequisite runs the
+// parent feature's rules while the child's rule is still open.
+#[derive(Debug, Default)]
+struct TraceRecorder {
+    entries: Vec<RuleTrace>,
+    open: Vec<usize>,
+}
+
+impl Default for ExperimentEvalContext {
+    fn default() -> Self {
+        Self {
+            url: None,
+            query_string_overrides: false,
+            enabled: true,
+            qa_mode: false,
+            forced_features: Arc::default(),
+            trace: None,
+            feature_results: None,
+            cycles: Cell::new(0),
+        }
+    }
+}
+
+impl ExperimentEvalContext {
+    /// Reads the page URL from the `url` attribute.
+    pub fn new(
+        user_attributes: &UserAttributes,
+        query_string_overrides: bool,
+    ) -> Self {
+        let url = user_attributes.find_value("url").and_then(|value| parse_url(&value.to_string()));
+        Self {
+            url,
+            query_string_overrides,
+            ..Default::default()
+        }
+    }
+
+    /// JS `enabled`: when false, nobody is put in an experiment.
+    pub fn with_enabled(
+        mut self,
+        enabled: bool,
+    ) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// JS `qaMode`: only forced variations are served; nobody is bucketed.
+    pub fn with_qa_mode(
+        mut self,
+        qa_mode: bool,
+    ) -> Self {
+        self.qa_mode = qa_mode;
+        self
+    }
+
+    /// Feature values that win over the payload wherever a feature is
+    /// evaluated, prerequisites and holdouts included.
+    pub fn with_forced_features(
+        mut self,
+        forced_features: Arc<HashMap<String, Value>>,
+    ) -> Self {
+        self.forced_features = forced_features;
+        self
+    }
+
+    /// Records a `RuleTrace` for every feature rule evaluated with this
+    /// context; see `begin_rule`.
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(RefCell::new(TraceRecorder::default()));
+        self
+    }
+
+    /// Remembers every feature evaluated with this context, so prerequisites
+    /// and holdouts shared by several features are only evaluated once.
+    pub fn with_shared_feature_results(mut self) -> Self {
+        self.feature_results = Some(RefCell::new(HashMap::new()));
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn is_qa_mode(&self) -> bool {
+        self.qa_mode
+    }
+
+    pub fn forced_feature(
+        &self,
+        feature_name: &str,
+    ) -> Option<&Value> {
+        self.forced_features.get(feature_name)
+    }
+
+    /// Evaluates against `url` instead of the `url` attribute.
+    pub fn with_url(
+        mut self,
+        url: &str,
+    ) -> Self {
+        self.url = parse_url(url);
+        self
+    }
+
+    /// The page URL, used for URL targeting.
+    pub fn url(&self) -> Option<&Url> {
+        self.url.as_ref()
+    }
+
+    /// JS `getQueryStringOverride`: `?{experiment_key}={index}` forces a
+    /// variation when the index is a valid variation.
+    pub fn query_string_override(
+        &self,
+        experiment_key: &str,
+        variations: usize,
+    ) -> Option<i64> {
+        if !self.query_string_overrides {
+            return None;
+        }
+        let url = self.url.as_ref()?;
+        let (_, value) = url.query_pairs().find(|(key, _)| key == experiment_key)?;
+        let variation = value.parse::<i64>().ok()?;
+        usize::try_from(variation).is_ok_and(|index| index < variations).then_some(variation)
+    }
+}
+
+/// Feature result sharing. Every call evaluates as usual unless the context
+/// was built `with_shared_feature_results`.
+impl ExperimentEvalContext {
+    /// The feature's result, evaluated at most once per context. Traced
+    /// evaluations are never shared since each visit must show up in the
+    /// trace.
+    pub fn feature_result(
+        &self,
+        feature_name: &str,
+        evaluate: impl FnOnce() -> FeatureResult,
+    ) -> FeatureResult {
+        let Some(feature_results) = self.feature_results.as_ref().filter(|_| self.trace.is_none()) else {
+            return evaluate();
+        };
+        if let Some(result) = feature_results.borrow().get(feature_name) {
+            return result.clone();
+        }
+
+        // A result that ran into a cycle depends on the path that led to it,
+        // so only cycle-free results are kept.
+        let cycles = self.cycles.get();
+        let result = evaluate();
+        if self.cycles.get() == cycles {
+            feature_results.borrow_mut().insert(feature_name.to_string(), result.clone());
+        }
+        result
+    }
+
+    /// Called wherever evaluation stops because of a prerequisite cycle.
+    pub fn record_cycle(&self) {
+        self.cycles.set(self.cycles.get() + 1);
+    }
+}
+
+/// Tracing hooks. Every call is a no-op unless the context was built
+/// `with_trace`, and `record_*` only touch the innermost open rule.
+impl ExperimentEvalContext {
+    pub fn begin_rule(
+        &self,
+        feature_id: &str,
+        rule_index: usize,
+        rule_id: Option<&String>,
+        kind: RuleTraceKind,
+    ) {
+        if let Some(trace) = &self.trace {
+            let mut trace = trace.borrow_mut();
+            trace.entries.push(RuleTrace {
+                feature_id: feature_id.to_string(),
+                rule_index,
+                rule_id: rule_id.cloned(),
+                kind,
+                outcome: RuleOutcome::NotMatched,
+                hash_attribute: None,
+                hash_value: None,
+                bucket: None,
+            });
+            let index = trace.entries.len() - 1;
+            trace.open.push(index);
+        }
+    }
+
+    pub fn record_outcome(
+        &self,
+        outcome: RuleOutcome,
+    ) {
+        self.update_open_rule(|rule| rule.outcome = outcome);
+    }
+
+    pub fn record_hash(
+        &self,
+        hash_attribute: &str,
+        hash_value: &GrowthBookAttributeValue,
+        bucket: Option<f32>,
+    ) {
+        self.update_open_rule(|rule| {
+            rule.hash_attribute = Some(hash_attribute.to_string());
+            rule.hash_value = Some(hash_value.to_value());
+            rule.bucket = bucket;
+        });
+    }
+
+    pub fn end_rule(&self) {
+        if let Some(trace) = &self.trace {
+            trace.borrow_mut().open.pop();
+        }
+    }
+
+    pub fn take_trace(self) -> Vec<RuleTrace> {
+        self.trace.map(|trace| trace.into_inner().entries).unwrap_or_default()
+    }
+
+    fn update_open_rule(
+        &self,
+        update: impl FnOnce(&mut RuleTrace),
+    ) {
+        if let Some(trace) = &self.trace {
+            let mut trace = trace.borrow_mut();
+            if let Some(&index) = trace.open.last() {
+                update(&mut trace.entries[index]);
+            }
+        }
+    }
+}
+
+// Server-rendered requests often only know the path, so relative URLs are
+// resolved against a placeholder origin.
+pub fn parse_url(url: &str) -> Option<Url> {
+    Url::parse(url).or_else(|_| Url::parse("http://localhost").and_then(|base| base.join(url))).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use serde::Deserialize;
+    use serde_json::Value;
+
+    use crate::experiment::eval_context::{parse_url, ExperimentEvalContext};
+
+    #[tokio::test]
+    async fn evaluate_get_query_string_override() -> Result<(), Box<dyn std::error::Error>> {
+        let cases = Cases::new();
+
+        for value in cases.get_query_string_override {
+            let eval_override = EvalQueryStringOverride::new(value);
+            let ctx = ExperimentEvalContext {
+                url: parse_url(&eval_override.url),
+                query_string_overrides: true,
+                ..Default::default()
+            };
+            let result = ctx.query_string_override(&eval_override.experiment_key, eval_override.variations);
+            if result != eval_override.result {
+                panic!("EvalQueryStringOverride failed: name='{}' expected={:?} result={result:?}", eval_override.name, eval_override.result)
+            }
+        }
+
+        Ok(())
+    }
+
+    #[derive(Deserialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    struct Cases {
+        get_query_string_override: Vec<Value>,
+    }
+
+    pub struct EvalQueryStringOverride {
+        name: String,
+        experiment_key: String,
+        url: String,
+        variations: usize,
+        result: Option<i64>,
+    }
+
+    impl EvalQueryStringOverride {
+        fn new(value: Value) -> Self {
+            let array = value.as_array().expect("Failed to convert to array");
+            Self {
+                name: array[0].as_str().expect("Failed to convert to str").to_string(),
+                experiment_key: array[1].as_str().expect("Failed to convert to str").to_string(),
+                url: array[2].as_str().expect("Failed to convert to str").to_string(),
+                variations: array[3].as_u64().expect("Failed to convert to u64") as usize,
+                result: array[4].as_i64(),
+            }
+        }
+    }
+
+    impl Cases {
+        pub fn new() -> Self {
+            let contents = fs::read_to_string("./tests/all_cases.json").expect("Should have been able to read the file");
+
+            serde_json::from_str(&contents).expect("Failed to create cases")
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod eval_context;
+pub mod redirect;
+pub mod url_target;
+pub mod use_case;
@@ -0,0 +1,86 @@
+use serde_json::Value;
+use url::form_urlencoded;
+use url::Url;
+
+use crate::experiment::eval_context::parse_url;
+use crate::experiment::url_target::UrlTarget;
+use crate::model_public::{Experiment, ExperimentResult, RedirectResult};
+
+pub struct Redirect;
+
+impl Redirect {
+    /// Auto experiments that may redirect: URL-targeted, not manual, and at
+    /// least one variation with a `urlRedirect`.
+    pub fn is_redirect_experiment(experiment: &Experiment) -> bool {
+        experiment.url_patterns.is_some() && !experiment.manual.unwrap_or(false) && experiment.variations.iter().any(|variation| url_redirect(variation).is_some())
+    }
+
+    /// The redirect for the assigned variation, if it has one. A redirect
+    /// that would land on a URL the experiment targets again is skipped so
+    /// users don't loop.
+    pub fn result(
+        experiment: &Experiment,
+        experiment_result: &ExperimentResult,
+        current_url: &str,
+    ) -> Option<RedirectResult> {
+        if !experiment_result.in_experiment {
+            return None;
+        }
+        let url_redirect = url_redirect(&experiment_result.value)?;
+        let url_with_params = if experiment.persist_query_string.unwrap_or(false) {
+            merge_query_strings(current_url, url_redirect)
+        } else {
+            url_redirect.to_string()
+        };
+
+        let patterns = experiment.url_patterns.as_deref().unwrap_or_default();
+        if UrlTarget::is_targeted(parse_url(&url_with_params).as_ref(), patterns) {
+            return None;
+        }
+
+        Some(RedirectResult {
+            url_redirect: url_redirect.to_string(),
+            url_with_params,
+            experiment_result: experiment_result.clone(),
+        })
+    }
+}
+
+fn url_redirect(variation: &Value) -> Option<&str> {
+    variation.get("urlRedirect").and_then(Value::as_str).filter(|it| !it.is_empty())
+}
+
+/// JS `mergeQueryStrings`: appends the current URL's query parameters the
+/// redirect doesn't already set. The redirect's own values win.
+fn merge_query_strings(
+    current_url: &str,
+    url_redirect: &str,
+) -> String {
+    let (Ok(current), Ok(redirect)) = (Url::parse(current_url), Url::parse(url_redirect)) else {
+        return url_redirect.to_string();
+    };
+
+    let mut serializer = form_urlencoded::Serializer::new(String::new());
+    let mut seen: Vec<String> = redirect.query_pairs().map(|(key, _)| key.into_owned()).collect();
+    for (key, value) in current.query_pairs() {
+        if !seen.contains(&key.to_string()) {
+            serializer.append_pair(&key, &value);
+            seen.push(key.into_owned());
+        }
+    }
+    let extra = serializer.finish();
+    if extra.is_empty() {
+        return url_redirect.to_string();
+    }
+
+    let (base, fragment) = url_redirect.split_once('#').map_or((url_redirect, None), |(base, fragment)| (base, Some(fragment)));
+    let separator = match redirect.query() {
+        Some(query) if !query.is_empty() => "&",
+        Some(_) => "",
+        None => "?",
+    };
+    match fragment {
+        Some(fragment) => format!("{base}{separator}{extra}#{fragment}"),
+        None => format!("{base}{separator}{extra}"),
+    }
+}
@@ -0,0 +1,102 @@
+use regex::{Regex, RegexBuilder};
+use url::{Position, Url};
+
+use crate::model_public::{UrlPattern, UrlPatternType};
+
+// Stands in for `*` wildcards while a simple pattern is parsed as a URL.
+const WILDCARD: &str = "_____";
+
+pub struct UrlTarget;
+
+impl UrlTarget {
+    /// JS `isURLTargeted`: the URL must match at least one include pattern
+    /// (when there are any) and no exclude pattern.
+    pub fn is_targeted(
+        url: Option<&Url>,
+        patterns: &[UrlPattern],
+    ) -> bool {
+        if patterns.is_empty() {
+            return false;
+        }
+
+        let mut has_include_rules = false;
+        let mut is_included = false;
+        for pattern in patterns {
+            let matched = url.is_some_and(|url| Self::matches(url, pattern));
+            if pattern.include == Some(false) {
+                if matched {
+                    return false;
+                }
+            } else {
+                has_include_rules = true;
+                is_included |= matched;
+            }
+        }
+
+        is_included || !has_include_rules
+    }
+
+    fn matches(
+        url: &Url,
+        pattern: &UrlPattern,
+    ) -> bool {
+        match pattern.kind {
+            UrlPatternType::Regex => match Regex::new(&pattern.pattern) {
+                Ok(regex) => regex.is_match(url.as_str()) || regex.is_match(&url[Position::BeforePath..]),
+                Err(_) => false,
+            },
+            UrlPatternType::Simple => simple_matches(url, &pattern.pattern),
+            UrlPatternType::Unknown => false,
+        }
+    }
+}
+
+/// JS `_evalSimpleUrlTarget`: host and path must match, plus the hash and
+/// every query parameter the pattern specifies. `*` matches anything.
+fn simple_matches(
+    actual: &Url,
+    pattern: &str,
+) -> bool {
+    // A bare `example.com/path` pattern is treated as https.
+    let host_end = pattern.find([':', '/', '?']).unwrap_or(pattern.len());
+    let pattern = if pattern[..host_end].contains('.') { format!("https://{pattern}") } else { pattern.to_string() };
+
+    let Ok(expected) = Url::parse(&format!("https://{WILDCARD}")).and_then(|base| base.join(&pattern.replace('*', WILDCARD))) else {
+        return false;
+    };
+
+    let mut parts = vec![(host(actual), host(&expected), false), (actual.path().to_string(), expected.path().to_string(), true)];
+    if let Some(fragment) = expected.fragment().filter(|it| !it.is_empty()) {
+        parts.push((actual.fragment().map(|it| format!("#{it}")).unwrap_or_default(), format!("#{fragment}"), false));
+    }
+    for (key, value) in expected.query_pairs() {
+        let actual_value = actual.query_pairs().find(|(actual_key, _)| actual_key == &key).map(|(_, it)| it.into_owned()).unwrap_or_default();
+        parts.push((actual_value, value.into_owned(), false));
+    }
+
+    parts.iter().all(|(actual, expected, is_path)| simple_part_matches(actual, expected, *is_path))
+}
+
+fn simple_part_matches(
+    actual: &str,
+    pattern: &str,
+    is_path: bool,
+) -> bool {
+    let mut escaped = regex::escape(pattern).replace(WILDCARD, ".*");
+    if is_path {
+        // Leading and trailing slashes are optional in paths.
+        let trimmed = escaped.strip_prefix('/').unwrap_or(&escaped);
+        let trimmed = trimmed.strip_suffix('/').unwrap_or(trimmed);
+        escaped = format!("/?{trimmed}/?");
+    }
+
+    RegexBuilder::new(&format!("^{escaped}$")).case_insensitive(true).build().is_ok_and(|regex| regex.is_match(actual))
+}
+
+fn host(url: &Url) -> String {
+    match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_string(),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use crate::condition::eval_context::{ConditionEvalContext, SavedGroups};
+use crate::dto::GrowthBookFeature;
+use crate::experiment::eval_context::ExperimentEvalContext;
+use crate::experiment::url_target::UrlTarget;
+use crate::extensions::FindGrowthBookAttribute;
+use crate::feature::feature_rule_experiment::{choose_variation, meta_value};
+use crate::feature::use_case::{evaluate_parent_conditions, ParentOutcome};
+use crate::filter::use_case::Filter;
+use crate::hash::{HashCode, HashCodeVersion};
+use crate::model_public::{Experiment, ExperimentResult, GrowthBookAttributeValue, UserAttributes};
+use crate::namespace::use_case::Namespace;
+use crate::range::model::Range;
+
+impl Experiment {
+    /// Runs an inline experiment, following the step order of JS
+    /// `runExperiment`. A user who isn't bucketed gets variation 0 with
+    /// `in_experiment = false`.
+    pub fn run(
+        &self,
+        user_attributes: &UserAttributes,
+        forced_variations: &Option<HashMap<String, i64>>,
+        all_features: &HashMap<String, GrowthBookFeature>,
+        saved_groups: &SavedGroups,
+        experiment_ctx: &ExperimentEvalContext,
+    ) -> ExperimentResult {
+        let (hash_attribute, hash_value) = self.hash_attribute_and_value(user_attributes);
+        let not_in_experiment = || self.result(-1, false, &hash_attribute, hash_value.as_ref(), None);
+
+        // Step 1: an experiment needs at least two variations.
+        if self.variations.len() < 2 {
+            return not_in_experiment();
+        }
+
+        // Step 2: nobody is in an experiment while experiments are disabled.
+        if !experiment_ctx.is_enabled() {
+            return not_in_experiment();
+        }
+
+        // Step 2.5: a query-string override wins over the remaining steps.
+        if let Some(query_string_override) = experiment_ctx.query_string_override(&self.key, self.variations.len()) {
+            return self.result(query_string_override, false, &hash_attribute, hash_value.as_ref(), None);
+        }
+
+        // Step 4: then a forced variation from the context.
+        if let Some(forced_variation) = forced_variations.as_ref().and_then(|forced| forced.get(&self.key)) {
+            return self.result(*forced_variation, false, &hash_attribute, hash_value.as_ref(), None);
+        }
+
+        // Step 5: inactive experiments are skipped.
+        if !self.active.unwrap_or(true) {
+            return not_in_experiment();
+        }
+
+        // Step 6: the user needs a value for the hash attribute.
+        let Some(user_value) = &hash_value else {
+            return not_in_experiment();
+        };
+
+        // Step 7: filters take precedence over the legacy namespace.
+        if let Some(filters) = &self.filters {
+            if Filter::is_filtered_out(filters, "id", user_attributes) {
+                return not_in_experiment();
+            }
+        } else if let Some((namespace, range)) = Namespace::get_range(self.namespace.as_ref()) {
+            if !Namespace::is_in(user_value, &namespace, &range) {
+                return not_in_experiment();
+            }
+        }
+
+        // Step 8: targeting condition. A non-object condition is ignored, as
+        // it is for feature rules.
+        if let Some(condition) = &self.condition {
+            if !condition.matches(&ConditionEvalContext::new(user_attributes, saved_groups)) {
+                return not_in_experiment();
+            }
+        }
+
+        // Step 8.05: prerequisites. Any failure, gating or not, excludes the
+        // user since there is no next rule to fall through to.
+        if let Some(parents) = &self.parent_conditions {
+            let outcome = evaluate_parent_conditions(parents, &self.key, &[], user_attributes, forced_variations, all_features, &None, saved_groups, experiment_ctx);
+            if !matches!(outcome, ParentOutcome::Continue) {
+                return not_in_experiment();
+            }
+        }
+
+        // Step 8.1: URL targeting.
+        if let Some(url_patterns) = &self.url_patterns {
+            if !UrlTarget::is_targeted(experiment_ctx.url(), url_patterns) {
+                return not_in_experiment();
+            }
+        }
+
+        // Step 9: hash the user into a bucket and pick the variation.
+        let seed = self.seed.clone().unwrap_or(self.key.clone());
+        let Some(bucket) = HashCode::hash_code(&user_value.to_string(), &seed, HashCodeVersion::from(self.hash_version)) else {
+            return not_in_experiment();
+        };
+        let ranges = Range::get_ranges(self.ranges.as_ref(), self.variations.len() as i64, &self.coverage, self.weights.clone());
+        let index = choose_variation(bucket, ranges);
+        if index < 0 {
+            return not_in_experiment();
+        }
+
+        // Step 11: a forced experiment serves its variation to everyone bucketed.
+        if let Some(force) = self.force {
+            return self.result(force, false, &hash_attribute, hash_value.as_ref(), None);
+        }
+
+        // Step 12: QA mode only serves forced variations.
+        if experiment_ctx.is_qa_mode() {
+            return not_in_experiment();
+        }
+
+        self.result(index, true, &hash_attribute, hash_value.as_ref(), Some(bucket))
+    }
+
+    /// JS `getHashAttribute`: the experiment's `hashAttribute` (default "id"),
+    /// or `fallbackAttribute` when the user has no value for the former.
+    fn hash_attribute_and_value(
+        &self,
+        user_attributes: &UserAttributes,
+    ) -> (String, Option<GrowthBookAttributeValue>) {
+        let hash_attribute = self.hash_attribute.clone().unwrap_or(String::from("id"));
+        let hash_value = non_empty_value(user_attributes, &hash_attribute);
+
+        if hash_value.is_none() {
+            if let Some(fallback_attribute) = &self.fallback_attribute {
+                if let Some(fallback_value) = non_empty_value(user_attributes, fallback_attribute) {
+                    return (fallback_attribute.clone(), Some(fallback_value));
+                }
+            }
+        }
+
+        (hash_attribute, hash_value)
+    }
+
+    /// JS `getResult`: an out-of-range variation (including the `-1` used for
+    /// "not bucketed") falls back to variation 0 and `in_experiment = false`.
+    fn result(
+        &self,
+        variation_id: i64,
+        hash_used: bool,
+        hash_attribute: &str,
+        hash_value: Option<&GrowthBookAttributeValue>,
+        bucket: Option<f32>,
+    ) -> ExperimentResult {
+        let (index, in_experiment) = match usize::try_from(variation_id) {
+            Ok(index) if index < self.variations.len() => (index, true),
+            _ => (0, false),
+        };
+        let (key, _pass_through) = meta_value(&self.meta, index);
+
+        ExperimentResult {
+            feature_id: String::new(),
+            experiment_key: self.key.clone(),
+            value: self.variations.get(index).cloned().unwrap_or_default(),
+            variation_id: index as i64,
+            in_experiment,
+            hash_used: in_experiment && hash_used,
+            hash_attribute: Some(hash_attribute.to_string()),
+            hash_value: hash_value.map(|value| value.to_value()),
+            bucket,
+            key,
+            sticky_bucket_used: false,
+        }
+    }
+}
+
+fn non_empty_value(
+    user_attributes: &UserAttributes,
+    attribute: &str,
+) -> Option<GrowthBookAttributeValue> {
+    user_attributes.find_value(attribute).filter(|value| !value.to_string().is_empty())
+}
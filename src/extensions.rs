@@ -1,6 +1,6 @@
 use serde_json::Value;
 
-use crate::model_public::{GrowthBookAttribute, GrowthBookAttributeValue};
+use crate::model_public::{GrowthBookAttribute, GrowthBookAttributeValue, UserAttributes};
 
 pub trait FindGrowthBookAttribute {
     fn find_value(
@@ -118,7 +118,16 @@ impl FindGrowthBookAttribute for Vec<GrowthBookAttribute> {
         &self,
         attribute_key: &str,
     ) -> Option<GrowthBookAttributeValue> {
-        look_for_attribute(0, attribute_key, self).map(|it| it.value)
+        find_path(self, &attribute_key.split('.').collect::<Vec<_>>()).cloned()
+    }
+}
+
+impl FindGrowthBookAttribute for UserAttributes {
+    fn find_value(
+        &self,
+        attribute_key: &str,
+    ) -> Option<GrowthBookAttributeValue> {
+        find_indexed_path(self, &attribute_key.split('.').collect::<Vec<_>>()).cloned()
     }
 }
 
@@ -127,29 +136,42 @@ impl FindGrowthBookAttribute for &[GrowthBookAttribute] {
         &self,
         attribute_key: &str,
     ) -> Option<GrowthBookAttributeValue> {
-        look_for_attribute(0, attribute_key, self).map(|it| it.value)
+        find_path(self, &attribute_key.split('.').collect::<Vec<_>>()).cloned()
     }
 }
 
-fn look_for_attribute(
-    split_index: usize,
-    attribute_key: &str,
-    user_attributes: &[GrowthBookAttribute],
-) -> Option<GrowthBookAttribute> {
-    let split = attribute_key.split('.').collect::<Vec<&str>>();
-    let key_part = split[split_index];
-    let option_attribute = user_attributes.iter().find(|item| item.key == key_part);
-    if let Some(found_attribute) = option_attribute {
-        if split.len().gt(&(split_index + 1)) {
-            match found_attribute.value.clone() {
-                GrowthBookAttributeValue::Object(it) => look_for_attribute(split_index + 1, attribute_key, &it),
-                GrowthBookAttributeValue::Empty => None,
-                _ => Some(found_attribute.clone()),
-            }
-        } else {
-            Some(found_attribute.clone())
-        }
-    } else {
-        None
+/// The value at a dotted attribute path, split into its parts. A path that
+/// runs into a scalar resolves to that scalar; one that runs into `null`
+/// resolves to nothing.
+pub(crate) fn find_path<'a, S: AsRef<str>>(
+    user_attributes: &'a [GrowthBookAttribute],
+    path: &[S],
+) -> Option<&'a GrowthBookAttributeValue> {
+    let (key_part, rest) = path.split_first()?;
+    let found_attribute = user_attributes.iter().find(|item| item.key == key_part.as_ref())?;
+    descend(&found_attribute.value, rest)
+}
+
+/// Like `find_path`, looking the top-level key up in the index.
+pub(crate) fn find_indexed_path<'a, S: AsRef<str>>(
+    user_attributes: &'a UserAttributes,
+    path: &[S],
+) -> Option<&'a GrowthBookAttributeValue> {
+    let (key_part, rest) = path.split_first()?;
+    descend(&user_attributes.get(key_part.as_ref())?.value, rest)
+}
+
+fn descend<'a, S: AsRef<str>>(
+    value: &'a GrowthBookAttributeValue,
+    rest: &[S],
+) -> Option<&'a GrowthBookAttributeValue> {
+    if rest.is_empty() {
+        return Some(value);
+    }
+
+    match value {
+        GrowthBookAttributeValue::Object(it) => find_path(it, rest),
+        GrowthBookAttributeValue::Empty => None,
+        it => Some(it),
     }
 }
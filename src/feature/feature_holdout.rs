@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::condition::eval_context::SavedGroups;
+use crate::dto::{GrowthBookFeature, GrowthBookFeatureHoldout};
+use crate::experiment::eval_context::ExperimentEvalContext;
+use crate::feature::feature_rule_experiment::meta_value;
+use crate::feature::use_case::evaluate_feature;
+use crate::model_public::{Experiment, TrackData, UserAttributes};
+use crate::sticky_bucket::StickyBucketService;
+
+impl GrowthBookFeatureHoldout {
+    /// Evaluates the holdout feature and returns the user's holdout exposure
+    /// if they are held out. The holdout experiment marks the measured
+    /// population's variation `passthrough`, so only users assigned another
+    /// variation get an experiment result. A missing or cyclic holdout
+    /// feature is ignored.
+    #[allow(clippy::too_many_arguments)]
+    pub fn evaluate(
+        &self,
+        feature_name: &str,
+        feature_name_decorate: &[String],
+        user_attributes: &UserAttributes,
+        forced_variations: &Option<HashMap<String, i64>>,
+        all_features: &HashMap<String, GrowthBookFeature>,
+        sticky_bucket_service: &Option<Arc<dyn StickyBucketService>>,
+        saved_groups: &SavedGroups,
+        experiment_ctx: &ExperimentEvalContext,
+    ) -> Option<TrackData> {
+        if self.id == feature_name || feature_name_decorate.contains(&self.id) {
+            experiment_ctx.record_cycle();
+            return None;
+        }
+        let mut updated_decorate = feature_name_decorate.to_vec();
+        updated_decorate.push(String::from(feature_name));
+        let result = evaluate_feature(
+            &self.id,
+            updated_decorate,
+            user_attributes,
+            forced_variations,
+            all_features,
+            sticky_bucket_service,
+            saved_groups,
+            experiment_ctx,
+        )?;
+
+        match (result.experiment, result.experiment_result) {
+            (Some(experiment), Some(experiment_result)) if experiment_result.in_experiment && !is_passthrough(&experiment, experiment_result.variation_id) => Some(TrackData {
+                experiment,
+                result: experiment_result,
+            }),
+            _ => None,
+        }
+    }
+}
+
+// Membership is the meta's call: a variation marked `passthrough` never holds
+// a user out, even when served from a sticky bucket another SDK saved.
+fn is_passthrough(
+    experiment: &Experiment,
+    variation_id: i64,
+) -> bool {
+    usize::try_from(variation_id).is_ok_and(|index| meta_value(&experiment.meta, index).1)
+}
@@ -4,23 +4,24 @@ use std::sync::Arc;
 use serde_json::Value;
 
 use crate::condition::eval_context::{ConditionEvalContext, SavedGroups};
-use crate::condition::use_case::ConditionsMatchesAttributes;
 use crate::dto::GrowthBookFeatureRuleExperiment;
+use crate::experiment::eval_context::ExperimentEvalContext;
 use crate::extensions::{FindGrowthBookAttribute, JsonHelper};
 use crate::hash::{HashCode, HashCodeVersion};
-use crate::model_public::{ExperimentResult, FeatureResult, GrowthBookAttribute};
+use crate::model_public::{ExperimentResult, FeatureResult, RuleOutcome, UserAttributes};
 use crate::namespace::use_case::Namespace;
 use crate::range::model::Range;
-use crate::sticky_bucket::StickyBucketService;
+use crate::sticky_bucket::{assignment_key, StickyAssignmentsDocument, StickyBucketService};
 
 impl GrowthBookFeatureRuleExperiment {
     pub fn get_match_value(
         &self,
         feature_name: &str,
-        user_attributes: &Vec<GrowthBookAttribute>,
+        user_attributes: &UserAttributes,
         forced_variations: &Option<HashMap<String, i64>>,
         sticky_bucket_service: &Option<Arc<dyn StickyBucketService>>,
         saved_groups: &SavedGroups,
+        experiment_ctx: &ExperimentEvalContext,
     ) -> Option<FeatureResult> {
         let feature_attribute = if let Some(hash_attribute) = &self.hash_attribute {
             if user_attributes.find_value(hash_attribute).is_some() {
@@ -38,34 +39,60 @@ impl GrowthBookFeatureRuleExperiment {
             self.get_fallback_attribute()
         };
 
-        self.check_experiment(&feature_name, user_attributes, forced_variations, &feature_attribute, sticky_bucket_service, saved_groups)
+        self.check_experiment(
+            &feature_name,
+            user_attributes,
+            forced_variations,
+            &feature_attribute,
+            sticky_bucket_service,
+            saved_groups,
+            experiment_ctx,
+        )
     }
 
     #[allow(clippy::too_many_arguments)]
     fn check_experiment(
         &self,
         feature_name: &&str,
-        user_attributes: &Vec<GrowthBookAttribute>,
+        user_attributes: &UserAttributes,
         forced_variations: &Option<HashMap<String, i64>>,
         feature_attribute: &str,
         sticky_bucket_service: &Option<Arc<dyn StickyBucketService>>,
         saved_groups: &SavedGroups,
+        experiment_ctx: &ExperimentEvalContext,
     ) -> Option<FeatureResult> {
-        let user_value = user_attributes.find_value(feature_attribute)?;
+        // Step 2 (JS runExperiment): with experiments disabled the rule is
+        // skipped, so the feature falls through to its next rule.
+        if !experiment_ctx.is_enabled() {
+            experiment_ctx.record_outcome(RuleOutcome::ExperimentsDisabled);
+            return None;
+        }
+
+        let Some(user_value) = user_attributes.find_value(feature_attribute) else {
+            experiment_ctx.record_outcome(RuleOutcome::MissingHashAttribute);
+            return None;
+        };
 
         // Step 4 (JS runExperiment): a forced variation fires before any
         // targeting or sticky-bucket logic.
-        if let Some(forced_variation) = self.forced_variation(feature_name, user_attributes, forced_variations) {
+        if let Some(forced_variation) = self.forced_variation(feature_name, user_attributes, forced_variations, experiment_ctx) {
             return Some(forced_variation);
         }
 
+        // Step 12 (JS runExperiment): QA mode only serves forced variations,
+        // so neither sticky buckets nor hashing assign anyone.
+        if experiment_ctx.is_qa_mode() {
+            experiment_ctx.record_outcome(RuleOutcome::ExperimentsDisabled);
+            return None;
+        }
+
         // Sticky Bucketing Logic
         if let Some(service) = sticky_bucket_service {
             if !self.disable_sticky_bucketing.unwrap_or(false) {
                 let bucket_version = self.bucket_version.unwrap_or(0);
                 let min_bucket_version = self.min_bucket_version.unwrap_or(0);
                 let meta_key = self.key.clone().unwrap_or_else(|| feature_name.to_string());
-                let sticky_key = format!("{}__{}", meta_key, bucket_version);
+                let sticky_key = assignment_key(&meta_key, bucket_version);
 
                 let fallback_attribute = self.get_fallback_attribute();
                 let fallback_value = if feature_attribute != fallback_attribute {
@@ -76,9 +103,9 @@ impl GrowthBookFeatureRuleExperiment {
 
                 // Helper to get assignment from doc
                 let get_assignment = |attr_name: &str, attr_val: &str| -> Option<(String, i64)> {
-                    let assignments = service.get_assignments(attr_name, attr_val)?;
+                    let doc = service.get_assignments(attr_name, attr_val)?;
                     // Iterate assignments to find matching key prefix
-                    for (key, variation_str) in assignments {
+                    for (key, variation_str) in doc.assignments {
                         // Check version blocking
                         if let Some(version_str) = key.strip_prefix(&format!("{}__", meta_key)) {
                             if let Ok(ver) = version_str.parse::<i64>() {
@@ -88,8 +115,10 @@ impl GrowthBookFeatureRuleExperiment {
                             }
                         }
 
+                        // Assignments store the variation's meta key (JS
+                        // `meta.findIndex(m => m.key === assigned)`).
                         if key == sticky_key {
-                            if let Ok(v) = variation_str.parse::<i64>() {
+                            if let Some(v) = self.variation_of_meta_key(&variation_str) {
                                 return Some((key, v));
                             }
                         }
@@ -103,6 +132,7 @@ impl GrowthBookFeatureRuleExperiment {
                 let hash_assign = get_assignment(feature_attribute, &user_value.to_string());
                 if let Some((_, var_id)) = hash_assign {
                     if var_id == -1 {
+                        experiment_ctx.record_outcome(RuleOutcome::StickyBucketBlocked);
                         return None;
                     } // Blocked
                     sticky_variation_id = Some(var_id);
@@ -111,14 +141,14 @@ impl GrowthBookFeatureRuleExperiment {
                     let fb_assign = get_assignment(&fallback_attribute, &fb_val.to_string());
                     if let Some((_, var_id)) = fb_assign {
                         if var_id == -1 {
+                            experiment_ctx.record_outcome(RuleOutcome::StickyBucketBlocked);
                             return None;
                         } // Blocked
                         sticky_variation_id = Some(var_id);
 
                         // Upgrade: Persist to hash attribute
-                        let mut new_assignment = HashMap::new();
-                        new_assignment.insert(sticky_key.clone(), var_id.to_string());
-                        service.save_assignments(feature_attribute, &user_value.to_string(), new_assignment);
+                        let (meta_value, _) = self.get_meta_value(var_id as usize);
+                        save_sticky_assignment(service.as_ref(), feature_attribute, &user_value.to_string(), sticky_key.clone(), meta_value);
                     }
                 }
 
@@ -129,9 +159,10 @@ impl GrowthBookFeatureRuleExperiment {
                         let (meta_value, _pass_through) = self.get_meta_value(usize_index);
                         return Some(FeatureResult::experiment(
                             value.clone(),
-                            self.model_experiment(),
+                            self.model_experiment(feature_name),
                             create_experiment_result(
                                 feature_name,
+                                self.experiment_key(feature_name),
                                 value.clone(),
                                 index,
                                 true, // HashUsed? Technically it's Sticky but we verify via sticky_bucket_used flag
@@ -154,18 +185,21 @@ impl GrowthBookFeatureRuleExperiment {
         // Step 7: exclude if the user is filtered out by namespace.
         if let Some((namespace, range)) = &self.namespace_range() {
             if !Namespace::is_in(&user_value, namespace, range) {
+                experiment_ctx.record_outcome(RuleOutcome::NotInNamespace);
                 return None;
             }
         }
 
         // Step 8: exclude if the rule's condition doesn't pass.
-        if let Some(feature_attributes) = self.conditions() {
-            if !feature_attributes.matches(&ConditionEvalContext::new(user_attributes, saved_groups)) {
+        if let Some(condition) = self.condition() {
+            if !condition.matches(&ConditionEvalContext::new(user_attributes, saved_groups)) {
+                experiment_ctx.record_outcome(RuleOutcome::ConditionFailed);
                 return None;
             }
         }
 
         let user_weight = HashCode::hash_code(&user_value.to_string(), &self.seed(feature_name), HashCodeVersion::from(self.hash_version)).unwrap_or(-1.0);
+        experiment_ctx.record_hash(feature_attribute, &user_value, Some(user_weight));
         let ranges = self.ranges();
         let index = choose_variation(user_weight, ranges);
         if index >= 0 {
@@ -173,7 +207,10 @@ impl GrowthBookFeatureRuleExperiment {
             // #18: more ranges than variations can yield an index past the end.
             // JS treats an invalid index as inExperiment=false; skip the rule
             // (via `?`) instead of panicking.
-            let value = self.variations.get(usize_index).cloned()?;
+            let Some(value) = self.variations.get(usize_index).cloned() else {
+                experiment_ctx.record_outcome(RuleOutcome::NotInCoverage);
+                return None;
+            };
             let (meta_value, pass_through) = self.get_meta_value(usize_index);
 
             // Save Sticky Bucket
@@ -181,19 +218,18 @@ impl GrowthBookFeatureRuleExperiment {
                 if let Some(service) = sticky_bucket_service {
                     let bucket_version = self.bucket_version.unwrap_or(0);
                     let meta_key = self.key.clone().unwrap_or_else(|| feature_name.to_string());
-                    let sticky_key = format!("{}__{}", meta_key, bucket_version);
-                    let mut new_assignment = HashMap::new();
-                    new_assignment.insert(sticky_key, index.to_string());
-                    service.save_assignments(feature_attribute, &user_value.to_string(), new_assignment);
+                    let sticky_key = assignment_key(&meta_key, bucket_version);
+                    save_sticky_assignment(service.as_ref(), feature_attribute, &user_value.to_string(), sticky_key, meta_value.clone());
                 }
             }
 
             if !pass_through {
                 return Some(FeatureResult::experiment(
                     value.clone(),
-                    self.model_experiment(),
+                    self.model_experiment(feature_name),
                     create_experiment_result(
                         feature_name,
+                        self.experiment_key(feature_name),
                         value.clone(),
                         index,
                         true,
@@ -205,6 +241,9 @@ impl GrowthBookFeatureRuleExperiment {
                     ),
                 ));
             }
+            experiment_ctx.record_outcome(RuleOutcome::Passthrough);
+        } else {
+            experiment_ctx.record_outcome(RuleOutcome::NotInCoverage);
         }
 
         None
@@ -213,40 +252,46 @@ impl GrowthBookFeatureRuleExperiment {
     fn forced_variation(
         &self,
         feature_name: &str,
-        user_attributes: &Vec<GrowthBookAttribute>,
+        user_attributes: &UserAttributes,
         forced_variations: &Option<HashMap<String, i64>>,
+        experiment_ctx: &ExperimentEvalContext,
     ) -> Option<FeatureResult> {
-        if let Some(forced_variations) = forced_variations {
-            if let Some(found_forced_variation) = forced_variations.get(feature_name) {
-                let hash_attribute = self.hash_attribute.clone().unwrap_or(self.get_fallback_attribute());
-                if let Some(user_value) = user_attributes.find_value(&hash_attribute) {
-                    // #18: a forced variation index from an untrusted response may be
-                    // negative or out of range. JS clamps an invalid index to
-                    // inExperiment=false; skip the forced variation here rather than
-                    // indexing out of bounds.
-                    let forced_variation_index = match usize::try_from(*found_forced_variation) {
-                        Ok(index) if index < self.variations.len() => index,
-                        _ => return None,
-                    };
-                    let value = self.variations[forced_variation_index].clone();
-                    let (meta_value, pass_through) = self.get_meta_value(forced_variation_index);
-                    if !pass_through {
-                        return Some(FeatureResult::experiment(
+        // A query-string override (JS step 2.5) takes precedence over the
+        // context's forced variations. Both are keyed by experiment key.
+        let experiment_key = self.experiment_key(feature_name);
+        let query_string_override = experiment_ctx.query_string_override(&experiment_key, self.variations.len());
+        if let Some(found_forced_variation) = query_string_override.as_ref().or_else(|| forced_variations.as_ref()?.get(&experiment_key)) {
+            let hash_attribute = self.hash_attribute.clone().unwrap_or(self.get_fallback_attribute());
+            if let Some(user_value) = user_attributes.find_value(&hash_attribute) {
+                // #18: a forced variation index from an untrusted response may be
+                // negative or out of range. JS clamps an invalid index to
+                // inExperiment=false; skip the forced variation here rather than
+                // indexing out of bounds.
+                let forced_variation_index = match usize::try_from(*found_forced_variation) {
+                    Ok(index) if index < self.variations.len() => index,
+                    _ => return None,
+                };
+                let value = self.variations[forced_variation_index].clone();
+                let (meta_value, pass_through) = self.get_meta_value(forced_variation_index);
+                if !pass_through {
+                    return Some(FeatureResult::experiment(
+                        value.clone(),
+                        self.model_experiment(feature_name),
+                        create_experiment_result(
+                            feature_name,
+                            self.experiment_key(feature_name),
                             value.clone(),
-                            self.model_experiment(),
-                            create_experiment_result(
-                                feature_name,
-                                value.clone(),
-                                *found_forced_variation,
-                                true,
-                                self.hash_attribute.clone(),
-                                Some(user_value.to_value()),
-                                None,
-                                meta_value,
-                                false,
-                            ),
-                        ));
-                    }
+                            *found_forced_variation,
+                            // Forced, not hashed: JS leaves hashUsed false so
+                            // the assignment isn't tracked.
+                            false,
+                            self.hash_attribute.clone(),
+                            Some(user_value.to_value()),
+                            None,
+                            meta_value,
+                            false,
+                        ),
+                    ));
                 }
             }
         }
@@ -257,29 +302,30 @@ impl GrowthBookFeatureRuleExperiment {
         &self,
         usize_index: usize,
     ) -> (String, bool) {
-        match &self.meta {
-            None => (format!("{usize_index}"), false),
-            Some(it) => {
-                if let Some(meta_value) = it.force_array(vec![]).get(usize_index) {
-                    let pass_through = if let Some(pass_through_value) = meta_value.get("passthrough") {
-                        pass_through_value.force_bool(false)
-                    } else {
-                        false
-                    };
-
-                    if let Some(key) = meta_value.get("key") {
-                        (key.force_string(""), pass_through)
-                    } else {
-                        (format!("{usize_index}"), pass_through)
-                    }
-                } else {
-                    (format!("{usize_index}"), false)
-                }
-            },
-        }
+        meta_value(&self.meta, usize_index)
+    }
+
+    // The index of the variation whose meta key is `meta_key`. Assignments
+    // saved before meta keys were stored hold the index itself, so one that
+    // matches no key but is a valid index still resolves.
+    fn variation_of_meta_key(
+        &self,
+        meta_key: &str,
+    ) -> Option<i64> {
+        (0..self.variations.len())
+            .find(|index| self.get_meta_value(*index).0 == meta_key)
+            .or_else(|| meta_key.parse::<usize>().ok().filter(|index| *index < self.variations.len()))
+            .map(|index| index as i64)
     }
 
-    fn get_fallback_attribute(&self) -> String {
+    fn experiment_key(
+        &self,
+        feature_name: &str,
+    ) -> String {
+        self.key.clone().unwrap_or(feature_name.to_string())
+    }
+
+    pub fn get_fallback_attribute(&self) -> String {
         self.fallback_attribute.clone().unwrap_or(String::from("id"))
     }
 }
@@ -287,6 +333,7 @@ impl GrowthBookFeatureRuleExperiment {
 #[allow(clippy::too_many_arguments)]
 fn create_experiment_result(
     feature_name: &str,
+    experiment_key: String,
     value: Value,
     variation_id: i64,
     hash_used: bool,
@@ -298,6 +345,7 @@ fn create_experiment_result(
 ) -> ExperimentResult {
     ExperimentResult {
         feature_id: String::from(feature_name),
+        experiment_key,
         value,
         variation_id,
         in_experiment: true,
@@ -310,7 +358,53 @@ fn create_experiment_result(
     }
 }
 
-fn choose_variation(
+// Adds the assignment, the variation's meta key, to the attribute value's
+// document, saving it only when that changes the document.
+fn save_sticky_assignment(
+    service: &dyn StickyBucketService,
+    attribute_name: &str,
+    attribute_value: &str,
+    sticky_key: String,
+    variation: String,
+) {
+    let mut doc = service
+        .get_assignments(attribute_name, attribute_value)
+        .unwrap_or_else(|| StickyAssignmentsDocument::new(attribute_name, attribute_value, HashMap::new()));
+    if doc.assignments.get(&sticky_key) != Some(&variation) {
+        doc.assignments.insert(sticky_key, variation);
+        service.save_assignments(doc);
+    }
+}
+
+/// The variation's `meta` key (defaulting to its index) and whether it is a
+/// passthrough variation.
+pub fn meta_value(
+    meta: &Option<Value>,
+    usize_index: usize,
+) -> (String, bool) {
+    match meta {
+        None => (format!("{usize_index}"), false),
+        Some(it) => {
+            if let Some(meta_value) = it.force_array(vec![]).get(usize_index) {
+                let pass_through = if let Some(pass_through_value) = meta_value.get("passthrough") {
+                    pass_through_value.force_bool(false)
+                } else {
+                    false
+                };
+
+                if let Some(key) = meta_value.get("key") {
+                    (key.force_string(""), pass_through)
+                } else {
+                    (format!("{usize_index}"), pass_through)
+                }
+            } else {
+                (format!("{usize_index}"), false)
+            }
+        },
+    }
+}
+
+pub fn choose_variation(
     user_weight: f32,
     ranges: Vec<Range>,
 ) -> i64 {
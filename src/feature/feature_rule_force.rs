@@ -1,34 +1,37 @@
 use crate::condition::eval_context::{ConditionEvalContext, SavedGroups};
-use crate::condition::use_case::ConditionsMatchesAttributes;
 use crate::coverage::model::Coverage;
 use crate::dto::GrowthBookFeatureRuleForce;
+use crate::experiment::eval_context::ExperimentEvalContext;
 use crate::extensions::FindGrowthBookAttribute;
-use crate::model_public::{FeatureResult, GrowthBookAttribute};
+use crate::model_public::{FeatureResult, RuleOutcome, UserAttributes};
 
 impl GrowthBookFeatureRuleForce {
     pub fn get_match_value(
         &self,
         feature_name: &str,
-        user_attributes: &Vec<GrowthBookAttribute>,
+        user_attributes: &UserAttributes,
         saved_groups: &SavedGroups,
+        experiment_ctx: &ExperimentEvalContext,
     ) -> Option<FeatureResult> {
         // Note: `filters` are evaluated once in the rule loop (get_value) for
         // every rule kind, so the force path no longer checks them here.
-        if let Some(feature_attributes) = self.conditions() {
-            if feature_attributes.matches(&ConditionEvalContext::new(user_attributes, saved_groups)) {
-                self.check_range_or_force(feature_name, user_attributes)
+        if let Some(condition) = self.condition() {
+            if condition.matches(&ConditionEvalContext::new(user_attributes, saved_groups)) {
+                self.check_range_or_force(feature_name, user_attributes, experiment_ctx).map(|result| self.with_tracks(result))
             } else {
+                experiment_ctx.record_outcome(RuleOutcome::ConditionFailed);
                 None
             }
         } else {
-            self.check_range_or_force(feature_name, user_attributes)
+            self.check_range_or_force(feature_name, user_attributes, experiment_ctx).map(|result| self.with_tracks(result))
         }
     }
 
     fn check_range_or_force(
         &self,
         feature_name: &str,
-        user_attributes: &Vec<GrowthBookAttribute>,
+        user_attributes: &UserAttributes,
+        experiment_ctx: &ExperimentEvalContext,
     ) -> Option<FeatureResult> {
         if let Some(range) = self.range() {
             let seed = self.seed.clone().unwrap_or(feature_name.to_string());
@@ -37,20 +40,34 @@ impl GrowthBookFeatureRuleForce {
             // `getHashAttribute`): the rule's `hashAttribute` if the user has it,
             // otherwise `fallbackAttribute` (defaulting to "id"). Previously this
             // hardcoded "id", ignoring `hashAttribute` entirely.
-            if let Some(hash_attribute) = &self.hash_attribute {
-                if let Some(user_value) = user_attributes.find_value(hash_attribute) {
-                    return Coverage::check(&user_value, None, Some(range), &seed, self.hash_version, self.force.clone());
-                }
-            }
+            let hash_attribute = match &self.hash_attribute {
+                Some(hash_attribute) if user_attributes.find_value(hash_attribute).is_some() => hash_attribute.clone(),
+                _ => self.get_fallback_attribute(),
+            };
+            let Some(user_value) = user_attributes.find_value(&hash_attribute) else {
+                experiment_ctx.record_outcome(RuleOutcome::MissingHashAttribute);
+                return None;
+            };
 
-            let fallback_attribute = self.get_fallback_attribute();
-            if let Some(user_value) = user_attributes.find_value(&fallback_attribute) {
-                Coverage::check(&user_value, None, Some(range), &seed, self.hash_version, self.force.clone())
+            let user_weight = Coverage::bucket(&user_value, &seed, self.hash_version);
+            experiment_ctx.record_hash(&hash_attribute, &user_value, user_weight);
+            if user_weight.is_some_and(|weight| Coverage::includes(weight, None, Some(range))) {
+                Some(FeatureResult::force(self.force.clone()))
             } else {
+                experiment_ctx.record_outcome(RuleOutcome::NotInCoverage);
                 None
             }
         } else {
             Some(FeatureResult::force(self.force.clone()))
         }
     }
+
+    // A matched rule reports its `tracks` so the client can fire them.
+    fn with_tracks(
+        &self,
+        mut result: FeatureResult,
+    ) -> FeatureResult {
+        result.tracks = self.tracks.clone();
+        result
+    }
 }
@@ -1,7 +1,6 @@
 use crate::condition::eval_context::{ConditionEvalContext, SavedGroups};
-use crate::condition::use_case::ConditionsMatchesAttributes;
 use crate::dto::GrowthBookFeatureRuleParentData;
-use crate::model_public::{FeatureResult, GrowthBookAttribute, GrowthBookAttributeValue};
+use crate::model_public::{FeatureResult, GrowthBookAttribute, GrowthBookAttributeValue, UserAttributes};
 
 impl GrowthBookFeatureRuleParentData {
     pub fn is_met(
@@ -9,9 +8,9 @@ impl GrowthBookFeatureRuleParentData {
         feature: FeatureResult,
         saved_groups: &SavedGroups,
     ) -> bool {
-        if let Some(feature_attributes) = self.conditions() {
-            let attributes = [GrowthBookAttribute::new(String::from("value"), GrowthBookAttributeValue::from(feature.value))];
-            feature_attributes.matches(&ConditionEvalContext::new(&attributes, saved_groups))
+        if let Some(condition) = self.condition() {
+            let attributes = UserAttributes::new(vec![GrowthBookAttribute::new(String::from("value"), GrowthBookAttributeValue::from(feature.value))]);
+            condition.matches(&ConditionEvalContext::new(&attributes, saved_groups))
         } else {
             true
         }
@@ -1,3 +1,4 @@
+mod feature_holdout;
 pub mod feature_rule_experiment;
 pub mod feature_rule_force;
 mod feature_rule_parent;
@@ -1,14 +1,17 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use serde_json::Value;
+
 use crate::condition::eval_context::SavedGroups;
-use crate::dto::{GrowthBookFeature, GrowthBookFeatureRuleKind, GrowthBookFeatureRuleParentData};
+use crate::dto::{GrowthBookFeature, GrowthBookFeatureRule, GrowthBookFeatureRuleKind, GrowthBookFeatureRuleParentData};
+use crate::experiment::eval_context::ExperimentEvalContext;
 use crate::filter::use_case::Filter;
-use crate::model_public::{FeatureResult, GrowthBookAttribute};
+use crate::model_public::{FeatureResult, RuleOutcome, UserAttributes};
 use crate::sticky_bucket::StickyBucketService;
 
 /// Outcome of evaluating a rule's `parentConditions`.
-enum ParentOutcome {
+pub enum ParentOutcome {
     /// All prerequisites passed; continue evaluating this rule.
     Continue,
     /// A non-gating prerequisite failed; skip this rule, try the next one.
@@ -25,58 +28,74 @@ impl GrowthBookFeature {
         &self,
         feature_name: &str,
         feature_name_decorate: Vec<String>,
-        user_attributes: &Vec<GrowthBookAttribute>,
+        user_attributes: &UserAttributes,
         forced_variations: &Option<HashMap<String, i64>>,
         all_features: &HashMap<String, GrowthBookFeature>,
         sticky_bucket_service: &Option<Arc<dyn StickyBucketService>>,
         saved_groups: &SavedGroups,
+        experiment_ctx: &ExperimentEvalContext,
     ) -> FeatureResult {
-        if let Some(rules) = &self.rules {
-            for rule in rules {
-                // parentConditions are evaluated first, for every rule kind.
-                if let Some(parents) = &rule.parent_conditions {
-                    match evaluate_parent_conditions(
-                        parents,
-                        feature_name,
-                        &feature_name_decorate,
-                        user_attributes,
-                        forced_variations,
-                        all_features,
-                        sticky_bucket_service,
-                        saved_groups,
-                    ) {
-                        ParentOutcome::ShortCircuit(result) => return *result,
-                        ParentOutcome::SkipRule => continue,
-                        ParentOutcome::Continue => {},
-                    }
-                }
+        // The holdout is evaluated before the feature's own rules: held-out
+        // users get the holdout value and see none of the feature's
+        // experiments; everyone else gets the feature's rules.
+        if let Some(holdout) = &self.holdout {
+            let held_out = holdout.evaluate(
+                feature_name,
+                &feature_name_decorate,
+                user_attributes,
+                forced_variations,
+                all_features,
+                sticky_bucket_service,
+                saved_groups,
+                experiment_ctx,
+            );
+            if let Some(track) = held_out {
+                let value = holdout.value.clone().or(self.default_value.clone()).unwrap_or(Value::Null);
+                return FeatureResult::holdout(value, track.experiment, track.result);
+            }
+        }
 
-                // filters (mutual-exclusion / namespaces) apply to every rule
-                // kind, matching the JS rule loop. Each filter hashes on its own
-                // attribute, defaulting to "id".
-                if let Some(filters) = rule.filters() {
-                    if Filter::is_filtered_out(filters, "id", user_attributes) {
-                        continue;
-                    }
-                }
+        self.evaluate_rules(
+            feature_name,
+            feature_name_decorate,
+            user_attributes,
+            forced_variations,
+            all_features,
+            sticky_bucket_service,
+            saved_groups,
+            experiment_ctx,
+        )
+    }
 
-                match &rule.kind {
-                    GrowthBookFeatureRuleKind::Force(it) => {
-                        if let Some(feature) = it.get_match_value(feature_name, user_attributes, saved_groups) {
-                            return feature;
-                        }
-                    },
-                    GrowthBookFeatureRuleKind::Rollout(it) => {
-                        if let Some(feature) = it.get_match_value(feature_name, user_attributes, saved_groups) {
-                            return feature;
-                        }
-                    },
-                    GrowthBookFeatureRuleKind::Experiment(it) => {
-                        if let Some(feature) = it.get_match_value(feature_name, user_attributes, forced_variations, sticky_bucket_service, saved_groups) {
-                            return feature;
-                        }
-                    },
-                    GrowthBookFeatureRuleKind::Empty => {},
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_rules(
+        &self,
+        feature_name: &str,
+        feature_name_decorate: Vec<String>,
+        user_attributes: &UserAttributes,
+        forced_variations: &Option<HashMap<String, i64>>,
+        all_features: &HashMap<String, GrowthBookFeature>,
+        sticky_bucket_service: &Option<Arc<dyn StickyBucketService>>,
+        saved_groups: &SavedGroups,
+        experiment_ctx: &ExperimentEvalContext,
+    ) -> FeatureResult {
+        if let Some(rules) = &self.rules {
+            for (index, rule) in rules.iter().enumerate() {
+                experiment_ctx.begin_rule(feature_name, index, rule.id.as_ref(), rule.trace_kind());
+                let result = evaluate_rule(
+                    rule,
+                    feature_name,
+                    &feature_name_decorate,
+                    user_attributes,
+                    forced_variations,
+                    all_features,
+                    sticky_bucket_service,
+                    saved_groups,
+                    experiment_ctx,
+                );
+                experiment_ctx.end_rule();
+                if let Some(result) = result {
+                    return result;
                 }
             }
         }
@@ -85,39 +104,100 @@ impl GrowthBookFeature {
     }
 }
 
+// One rule of the JS rule loop. `Some` ends the feature's evaluation, either
+// with the rule's value or with a blocking prerequisite.
 #[allow(clippy::too_many_arguments)]
-fn evaluate_parent_conditions(
+fn evaluate_rule(
+    rule: &GrowthBookFeatureRule,
+    feature_name: &str,
+    feature_name_decorate: &[String],
+    user_attributes: &UserAttributes,
+    forced_variations: &Option<HashMap<String, i64>>,
+    all_features: &HashMap<String, GrowthBookFeature>,
+    sticky_bucket_service: &Option<Arc<dyn StickyBucketService>>,
+    saved_groups: &SavedGroups,
+    experiment_ctx: &ExperimentEvalContext,
+) -> Option<FeatureResult> {
+    // parentConditions are evaluated first, for every rule kind.
+    if let Some(parents) = &rule.parent_conditions {
+        match evaluate_parent_conditions(
+            parents,
+            feature_name,
+            feature_name_decorate,
+            user_attributes,
+            forced_variations,
+            all_features,
+            sticky_bucket_service,
+            saved_groups,
+            experiment_ctx,
+        ) {
+            ParentOutcome::ShortCircuit(result) => {
+                experiment_ctx.record_outcome(RuleOutcome::ParentBlocked);
+                return Some(*result);
+            },
+            ParentOutcome::SkipRule => {
+                experiment_ctx.record_outcome(RuleOutcome::ParentSkipped);
+                return None;
+            },
+            ParentOutcome::Continue => {},
+        }
+    }
+
+    // filters (mutual-exclusion / namespaces) apply to every rule
+    // kind, matching the JS rule loop. Each filter hashes on its own
+    // attribute, defaulting to "id".
+    if let Some(filters) = rule.filters() {
+        if Filter::is_filtered_out(filters, "id", user_attributes) {
+            experiment_ctx.record_outcome(RuleOutcome::FilteredOut);
+            return None;
+        }
+    }
+
+    let result = match &rule.kind {
+        GrowthBookFeatureRuleKind::Force(it) => it.get_match_value(feature_name, user_attributes, saved_groups, experiment_ctx),
+        GrowthBookFeatureRuleKind::Rollout(it) => it.get_match_value(feature_name, user_attributes, saved_groups, experiment_ctx),
+        GrowthBookFeatureRuleKind::Experiment(it) => it.get_match_value(feature_name, user_attributes, forced_variations, sticky_bucket_service, saved_groups, experiment_ctx),
+        GrowthBookFeatureRuleKind::Empty => None,
+    };
+    if result.is_some() {
+        experiment_ctx.record_outcome(RuleOutcome::Matched);
+    }
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn evaluate_parent_conditions(
     parents: &[GrowthBookFeatureRuleParentData],
     feature_name: &str,
     feature_name_decorate: &[String],
-    user_attributes: &Vec<GrowthBookAttribute>,
+    user_attributes: &UserAttributes,
     forced_variations: &Option<HashMap<String, i64>>,
     all_features: &HashMap<String, GrowthBookFeature>,
     sticky_bucket_service: &Option<Arc<dyn StickyBucketService>>,
     saved_groups: &SavedGroups,
+    experiment_ctx: &ExperimentEvalContext,
 ) -> ParentOutcome {
     for parent in parents {
         let parent_feature_name = &parent.id;
         if feature_name_decorate.contains(parent_feature_name) {
+            experiment_ctx.record_cycle();
             return ParentOutcome::ShortCircuit(Box::new(FeatureResult::cyclic_prerequisite()));
         }
 
         let mut updated_decorate = feature_name_decorate.to_vec();
         updated_decorate.push(String::from(feature_name));
 
-        let parent_response = if let Some(parent_feature) = all_features.get(parent_feature_name) {
-            parent_feature.get_value(
-                parent_feature_name,
-                updated_decorate,
-                user_attributes,
-                forced_variations,
-                all_features,
-                sticky_bucket_service,
-                saved_groups,
-            )
-        } else {
-            FeatureResult::unknown_feature()
-        };
+        let parent_response = evaluate_feature(
+            parent_feature_name,
+            updated_decorate,
+            user_attributes,
+            forced_variations,
+            all_features,
+            sticky_bucket_service,
+            saved_groups,
+            experiment_ctx,
+        )
+        .unwrap_or_else(FeatureResult::unknown_feature);
 
         if parent_response.source == "cyclicPrerequisite" {
             return ParentOutcome::ShortCircuit(Box::new(FeatureResult::cyclic_prerequisite()));
@@ -135,3 +215,37 @@ fn evaluate_parent_conditions(
 
     ParentOutcome::Continue
 }
+
+/// Evaluates a feature by name, as the top-level feature or as another's
+/// prerequisite or holdout: a forced value wins over the payload (JS
+/// `_evalFeature`). `None` when the feature is neither forced nor in the
+/// payload.
+#[allow(clippy::too_many_arguments)]
+pub fn evaluate_feature(
+    feature_name: &str,
+    feature_name_decorate: Vec<String>,
+    user_attributes: &UserAttributes,
+    forced_variations: &Option<HashMap<String, i64>>,
+    all_features: &HashMap<String, GrowthBookFeature>,
+    sticky_bucket_service: &Option<Arc<dyn StickyBucketService>>,
+    saved_groups: &SavedGroups,
+    experiment_ctx: &ExperimentEvalContext,
+) -> Option<FeatureResult> {
+    if let Some(value) = experiment_ctx.forced_feature(feature_name) {
+        return Some(FeatureResult::forced_feature(value.clone()));
+    }
+
+    let feature = all_features.get(feature_name)?;
+    Some(experiment_ctx.feature_result(feature_name, || {
+        feature.get_value(
+            feature_name,
+            feature_name_decorate,
+            user_attributes,
+            forced_variations,
+            all_features,
+            sticky_bucket_service,
+            saved_groups,
+            experiment_ctx,
+        )
+    }))
+}
@@ -2,7 +2,7 @@ use serde_json::Value;
 
 use crate::extensions::{FindGrowthBookAttribute, JsonHelper};
 use crate::hash::{HashCode, HashCodeVersion};
-use crate::model_public::GrowthBookAttribute;
+use crate::model_public::UserAttributes;
 use crate::range::model::Range;
 
 pub struct Filter;
@@ -18,7 +18,7 @@ impl Filter {
     pub fn is_filtered_out(
         filters: &Value,
         default_attribute: &str,
-        user_attributes: &Vec<GrowthBookAttribute>,
+        user_attributes: &UserAttributes,
     ) -> bool {
         filters.force_array(vec![]).iter().any(|filter| {
             let attribute = filter.get_string("attribute", default_attribute);
@@ -1,19 +1,51 @@
 use std::time::Duration;
 
-use reqwest::header::USER_AGENT;
+use reqwest::header::{ACCEPT, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, USER_AGENT};
+use reqwest::{Response, StatusCode};
 use reqwest_middleware::ClientWithMiddleware;
 
-use crate::dto::GrowthBookResponse;
+use crate::dto::{GrowthBookResponse, RemoteEvalRequest};
 use crate::env::Environment;
 use crate::error::GrowthbookError;
 use crate::infra::HttpClient;
 
+/// `ETag` / `Last-Modified` of the last payload, sent back on the next
+/// request so an unchanged payload isn't downloaded again.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CacheValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    fn from_response(response: &Response) -> Self {
+        let header = |name| response.headers().get(name).and_then(|value| value.to_str().ok()).map(String::from);
+        Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Outcome of a conditional fetch. The payload is boxed because it is large
+/// relative to `NotModified`.
+pub enum FeaturesFetch {
+    Modified(Box<GrowthBookResponse>, CacheValidators),
+    /// 304: the payload the validators came from is still current.
+    NotModified,
+}
+
 #[derive(Clone, Debug)]
 pub struct GrowthbookGateway {
     pub url: String,
     pub user_agent: String,
     sdk_key: String,
     pub client: ClientWithMiddleware,
+    streaming_client: ClientWithMiddleware,
 }
 impl GrowthbookGateway {
     pub fn new(
@@ -29,20 +61,72 @@ impl GrowthbookGateway {
                 Environment::string_or_default("CARGO_PKG_VERSION", "1.0.0")
             ),
             client: HttpClient::create_http_client("growthbook", timeout)?,
+            streaming_client: HttpClient::create_streaming_http_client("growthbook-streaming", timeout)?,
             sdk_key: sdk_key.to_string(),
         })
     }
 
-    pub async fn get_features(
+    /// Fetches the payload unless it still matches `validators`.
+    pub async fn get_features_if_modified(
         &self,
-        sdk_key: Option<&str>,
+        validators: Option<&CacheValidators>,
+    ) -> Result<FeaturesFetch, GrowthbookError> {
+        let url = format!("{}/api/features/{}", self.url, self.sdk_key);
+        let mut request = self.client.get(url).header(USER_AGENT, self.user_agent.clone());
+        if let Some(etag) = validators.and_then(|it| it.etag.as_ref()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = validators.and_then(|it| it.last_modified.as_ref()) {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        let send_result = request.send().await.map_err(GrowthbookError::from)?;
+
+        if send_result.status() == StatusCode::NOT_MODIFIED {
+            return Ok(FeaturesFetch::NotModified);
+        }
+
+        let validators = CacheValidators::from_response(&send_result);
+        let response = send_result.json::<GrowthBookResponse>().await.map_err(GrowthbookError::from)?;
+
+        Ok(FeaturesFetch::Modified(Box::new(response), validators))
+    }
+
+    /// Remote evaluation: the server evaluates the payload for the request's
+    /// attributes and returns the resolved features.
+    pub async fn post_remote_eval(
+        &self,
+        request: &RemoteEvalRequest,
     ) -> Result<GrowthBookResponse, GrowthbookError> {
-        let sdk = sdk_key.unwrap_or(self.sdk_key.as_str());
-        let url = format!("{}/api/features/{}", self.url, sdk);
-        let send_result = self.client.get(url).header(USER_AGENT, self.user_agent.clone()).send().await.map_err(GrowthbookError::from)?;
+        let url = format!("{}/api/eval/{}", self.url, self.sdk_key);
+        let body = serde_json::to_vec(request).map_err(GrowthbookError::from)?;
+        let send_result = self
+            .client
+            .post(url)
+            .header(USER_AGENT, self.user_agent.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(GrowthbookError::from)?;
 
         let response = send_result.json::<GrowthBookResponse>().await.map_err(GrowthbookError::from)?;
 
         Ok(response)
     }
+
+    /// Opens the server-sent events stream (`/sub/{clientKey}`). The
+    /// response body carries the events.
+    pub async fn subscribe(&self) -> Result<Response, GrowthbookError> {
+        let url = format!("{}/sub/{}", self.url, self.sdk_key);
+        let response = self
+            .streaming_client
+            .get(url)
+            .header(USER_AGENT, self.user_agent.clone())
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(GrowthbookError::from)?;
+
+        response.error_for_status().map_err(GrowthbookError::from)
+    }
 }
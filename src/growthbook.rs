@@ -1,18 +1,143 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Deref;
 use std::sync::Arc;
 
+use serde_json::Value;
+
 use crate::condition::eval_context::SavedGroups;
-use crate::dto::GrowthBookFeature;
-use crate::model_public::{FeatureResult, GrowthBookAttribute};
-use crate::sticky_bucket::StickyBucketService;
+use crate::dto::{Condition, ConditionError, GrowthBookFeature, GrowthBookFeatureRuleKind, GrowthBookFeatureRuleParentData};
+use crate::experiment::eval_context::ExperimentEvalContext;
+use crate::experiment::redirect::Redirect;
+use crate::extensions::FindGrowthBookAttribute;
+use crate::feature::use_case::evaluate_feature;
+use crate::model_public::{Experiment, ExperimentResult, FeatureExplanation, FeatureResult, GrowthBookAttribute, RedirectResult, UserAttributes};
+use crate::sticky_bucket::{ReadOnlyStickyBuckets, StickyBucketService};
 
 #[derive(Debug, Clone)]
 pub struct GrowthBook {
     pub forced_variations: Option<HashMap<String, i64>>,
+    /// Forced variations set by the application. They take precedence over
+    /// the payload's `forced_variations` and are kept across refreshes.
+    pub local_forced_variations: HashMap<String, i64>,
+    /// Feature values pinned regardless of rules (source `override`).
+    pub forced_features: Arc<HashMap<String, Value>>,
     pub features: HashMap<String, GrowthBookFeature>,
     pub attributes: Option<HashMap<String, GrowthBookAttribute>>,
     pub sticky_bucket_service: Option<Arc<dyn StickyBucketService>>,
     pub saved_groups: SavedGroups,
+    /// Auto experiments from the payload's `experiments` array.
+    pub experiments: Vec<Experiment>,
+    /// Honor `?{experiment_key}={variation}` in the `url` attribute. Off by
+    /// default.
+    pub query_string_overrides: bool,
+    /// Global kill switch: when false, nobody is put in an experiment.
+    pub enabled: bool,
+    /// Only forced variations are served; nobody is bucketed.
+    pub qa_mode: bool,
+}
+
+impl Default for GrowthBook {
+    fn default() -> Self {
+        Self {
+            forced_variations: None,
+            local_forced_variations: HashMap::new(),
+            forced_features: Arc::default(),
+            features: HashMap::new(),
+            attributes: None,
+            sticky_bucket_service: None,
+            saved_groups: SavedGroups::new(),
+            experiments: Vec::new(),
+            query_string_overrides: false,
+            enabled: true,
+            qa_mode: false,
+        }
+    }
+}
+
+/// A user's attributes merged with the snapshot's, and the forced
+/// variations and sticky bucket storage its evaluations use. Built once to
+/// evaluate any number of features for the same user.
+#[derive(Debug, Clone)]
+pub(crate) struct PreparedUser {
+    pub(crate) attributes: UserAttributes,
+    pub(crate) forced_variations: Arc<Option<HashMap<String, i64>>>,
+    pub(crate) sticky_bucket_service: Option<Arc<dyn StickyBucketService>>,
+}
+
+/// The state evaluations run against: the payload plus the client's
+/// settings. `GrowthBookClient` shares it as an immutable `Arc` and swaps in
+/// a new one on every refresh or setter. Derefs to the `GrowthBook` it was
+/// built from; the forced variations are merged once, when it's built.
+#[derive(Debug, Clone)]
+pub struct FeatureSnapshot {
+    growthbook: GrowthBook,
+    forced_variations: Arc<Option<HashMap<String, i64>>>,
+}
+
+impl FeatureSnapshot {
+    pub(crate) fn new(growthbook: GrowthBook) -> Self {
+        let forced_variations = Arc::new(growthbook.effective_forced_variations());
+        Self { growthbook, forced_variations }
+    }
+
+    pub fn check(
+        &self,
+        flag_name: &str,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> FeatureResult {
+        self.growthbook.check_prepared(flag_name, &self.prepare_user(option_user_attributes))
+    }
+
+    /// Evaluates like `check`, also returning the trace of every rule visited.
+    pub fn explain(
+        &self,
+        flag_name: &str,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> FeatureExplanation {
+        self.growthbook.explain_prepared(flag_name, &self.prepare_user(option_user_attributes))
+    }
+
+    /// See [`GrowthBook::evaluate_all`].
+    pub fn evaluate_all(
+        &self,
+        prefix: &str,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> HashMap<String, FeatureResult> {
+        self.growthbook.evaluate_all_prepared(prefix, &self.prepare_user(option_user_attributes))
+    }
+
+    pub fn run(
+        &self,
+        experiment: &Experiment,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> ExperimentResult {
+        self.growthbook.run_prepared(experiment, &self.prepare_user(option_user_attributes))
+    }
+
+    /// See [`GrowthBook::run_redirect_experiments`].
+    pub fn run_redirect_experiments(
+        &self,
+        current_url: &str,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> (Vec<ExperimentResult>, Option<RedirectResult>) {
+        self.growthbook.run_redirect_experiments_prepared(current_url, &self.prepare_user(option_user_attributes))
+    }
+
+    // Like `GrowthBook::prepare_user`, sharing the merged forced variations.
+    pub(crate) fn prepare_user(
+        &self,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> PreparedUser {
+        self.growthbook.prepare_user_with(option_user_attributes, Arc::clone(&self.forced_variations))
+    }
+}
+
+impl Deref for FeatureSnapshot {
+    type Target = GrowthBook;
+
+    fn deref(&self) -> &GrowthBook {
+        &self.growthbook
+    }
 }
 
 impl GrowthBook {
@@ -21,34 +146,273 @@ impl GrowthBook {
         flag_name: &str,
         option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
     ) -> FeatureResult {
-        if let Some(feature) = self.features.get(flag_name) {
-            // Merge instance attributes with call-time attributes
-            let mut merged_attributes = Vec::new();
-
-            // Add instance attributes first
-            if let Some(instance_attrs) = &self.attributes {
-                for attr in instance_attrs.values() {
-                    merged_attributes.push(attr.clone());
-                }
+        self.check_prepared(flag_name, &self.prepare_user(option_user_attributes))
+    }
+
+    /// Evaluates like `check`, also returning the trace of every rule visited.
+    pub fn explain(
+        &self,
+        flag_name: &str,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> FeatureExplanation {
+        self.explain_prepared(flag_name, &self.prepare_user(option_user_attributes))
+    }
+
+    /// Evaluates every feature (payload and forced) whose key starts with
+    /// `prefix`, against one set of attributes. Prerequisites and holdouts
+    /// shared between features are evaluated once.
+    pub fn evaluate_all(
+        &self,
+        prefix: &str,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> HashMap<String, FeatureResult> {
+        self.evaluate_all_prepared(prefix, &self.prepare_user(option_user_attributes))
+    }
+
+    pub fn run(
+        &self,
+        experiment: &Experiment,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> ExperimentResult {
+        self.run_prepared(experiment, &self.prepare_user(option_user_attributes))
+    }
+
+    /// Runs the payload's redirect experiments against `current_url` in
+    /// order, stopping at the first one that redirects. Returns every result
+    /// the user was bucketed into (for tracking) and the redirect, if any.
+    pub fn run_redirect_experiments(
+        &self,
+        current_url: &str,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> (Vec<ExperimentResult>, Option<RedirectResult>) {
+        self.run_redirect_experiments_prepared(current_url, &self.prepare_user(option_user_attributes))
+    }
+
+    /// The attribute name -> value pairs whose sticky bucket documents an
+    /// evaluation may read: every experiment rule's hash and fallback
+    /// attribute the user has a value for (JS `_getStickyBucketAttributes`).
+    pub fn sticky_bucket_attributes(
+        &self,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> HashMap<String, String> {
+        self.sticky_bucket_attributes_of(&self.merged_attributes(option_user_attributes))
+    }
+
+    /// Every part of the payload's conditions that can't be evaluated as
+    /// written, located in the payload (e.g.
+    /// `features.checkout.rules[0].condition.country.$regex`). Such parts
+    /// never match; see [`ConditionError`].
+    pub fn condition_errors(&self) -> Vec<ConditionError> {
+        let mut errors = Vec::new();
+
+        let mut feature_names: Vec<_> = self.features.keys().collect();
+        feature_names.sort();
+        for feature_name in feature_names {
+            for (index, rule) in self.features[feature_name].rules.iter().flatten().enumerate() {
+                let location = format!("features.{}.rules[{}]", feature_name, index);
+                collect_condition_errors(&mut errors, &location, rule.condition(), rule.parent_conditions.as_deref());
             }
+        }
+
+        for (index, experiment) in self.experiments.iter().enumerate() {
+            collect_condition_errors(&mut errors, &format!("experiments[{}]", index), experiment.condition.as_ref(), experiment.parent_conditions.as_deref());
+        }
+
+        errors
+    }
+
+    pub(crate) fn prepare_user(
+        &self,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> PreparedUser {
+        self.prepare_user_with(option_user_attributes, Arc::new(self.effective_forced_variations()))
+    }
+
+    fn prepare_user_with(
+        &self,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+        forced_variations: Arc<Option<HashMap<String, i64>>>,
+    ) -> PreparedUser {
+        PreparedUser {
+            attributes: self.merged_attributes(option_user_attributes),
+            forced_variations,
+            sticky_bucket_service: self.sticky_bucket_service.clone(),
+        }
+    }
+
+    pub(crate) fn check_prepared(
+        &self,
+        flag_name: &str,
+        user: &PreparedUser,
+    ) -> FeatureResult {
+        self.evaluate(flag_name, user, &self.experiment_ctx(&user.attributes))
+    }
+
+    pub(crate) fn explain_prepared(
+        &self,
+        flag_name: &str,
+        user: &PreparedUser,
+    ) -> FeatureExplanation {
+        // Explaining must not enroll the user, so sticky buckets are read but
+        // never saved.
+        let user = PreparedUser {
+            sticky_bucket_service: user.sticky_bucket_service.clone().map(ReadOnlyStickyBuckets::wrap),
+            ..user.clone()
+        };
+        let experiment_ctx = self.experiment_ctx(&user.attributes).with_trace();
+        let result = self.evaluate(flag_name, &user, &experiment_ctx);
+        FeatureExplanation {
+            result,
+            trace: experiment_ctx.take_trace(),
+        }
+    }
+
+    pub(crate) fn evaluate_all_prepared(
+        &self,
+        prefix: &str,
+        user: &PreparedUser,
+    ) -> HashMap<String, FeatureResult> {
+        let experiment_ctx = self.experiment_ctx(&user.attributes).with_shared_feature_results();
+
+        self.features
+            .keys()
+            .chain(self.forced_features.keys())
+            .filter(|flag_name| flag_name.starts_with(prefix))
+            .map(|flag_name| (flag_name.clone(), self.evaluate(flag_name, user, &experiment_ctx)))
+            .collect()
+    }
+
+    pub(crate) fn run_prepared(
+        &self,
+        experiment: &Experiment,
+        user: &PreparedUser,
+    ) -> ExperimentResult {
+        let experiment_ctx = self.experiment_ctx(&user.attributes);
+        experiment.run(&user.attributes, &user.forced_variations, &self.features, &self.saved_groups, &experiment_ctx)
+    }
 
-            // Add/Override with call-time attributes
-            if let Some(call_attrs) = option_user_attributes {
-                merged_attributes.extend(call_attrs.clone());
+    pub(crate) fn run_redirect_experiments_prepared(
+        &self,
+        current_url: &str,
+        user: &PreparedUser,
+    ) -> (Vec<ExperimentResult>, Option<RedirectResult>) {
+        let experiment_ctx = self.experiment_ctx(&user.attributes).with_url(current_url);
+
+        let mut results = Vec::new();
+        for experiment in self.experiments.iter().filter(|it| Redirect::is_redirect_experiment(it)) {
+            let result = experiment.run(&user.attributes, &user.forced_variations, &self.features, &self.saved_groups, &experiment_ctx);
+            let redirect = Redirect::result(experiment, &result, current_url);
+            if result.in_experiment {
+                results.push(result);
             }
+            if redirect.is_some() {
+                return (results, redirect);
+            }
+        }
+
+        (results, None)
+    }
+
+    pub(crate) fn sticky_bucket_attributes_of(
+        &self,
+        user_attributes: &UserAttributes,
+    ) -> HashMap<String, String> {
+        let mut attribute_names = BTreeSet::new();
+        for rule in self.features.values().flat_map(|feature| feature.rules.iter().flatten()) {
+            if let GrowthBookFeatureRuleKind::Experiment(experiment) = &rule.kind {
+                attribute_names.insert(experiment.hash_attribute.clone().unwrap_or(String::from("id")));
+                attribute_names.insert(experiment.get_fallback_attribute());
+            }
+        }
+
+        attribute_names
+            .into_iter()
+            .filter_map(|name| {
+                let value = user_attributes.find_value(&name)?.to_string();
+                (!value.is_empty()).then_some((name, value))
+            })
+            .collect()
+    }
+
+    fn evaluate(
+        &self,
+        flag_name: &str,
+        user: &PreparedUser,
+        experiment_ctx: &ExperimentEvalContext,
+    ) -> FeatureResult {
+        evaluate_feature(
+            flag_name,
+            vec![],
+            &user.attributes,
+            &user.forced_variations,
+            &self.features,
+            &user.sticky_bucket_service,
+            &self.saved_groups,
+            experiment_ctx,
+        )
+        .unwrap_or_else(FeatureResult::unknown_feature)
+    }
+
+    fn experiment_ctx(
+        &self,
+        user_attributes: &UserAttributes,
+    ) -> ExperimentEvalContext {
+        ExperimentEvalContext::new(user_attributes, self.query_string_overrides)
+            .with_enabled(self.enabled)
+            .with_qa_mode(self.qa_mode)
+            .with_forced_features(self.forced_features.clone())
+    }
 
-            feature.get_value(
-                flag_name,
-                vec![],
-                &merged_attributes,
-                &self.forced_variations,
-                &self.features,
-                &self.sticky_bucket_service,
-                &self.saved_groups,
-            )
-        } else {
-            FeatureResult::unknown_feature()
+    // Local forced variations override the payload's.
+    fn effective_forced_variations(&self) -> Option<HashMap<String, i64>> {
+        if self.local_forced_variations.is_empty() {
+            return self.forced_variations.clone();
         }
+
+        let mut merged = self.forced_variations.clone().unwrap_or_default();
+        merged.extend(self.local_forced_variations.iter().map(|(key, variation)| (key.clone(), *variation)));
+        Some(merged)
+    }
+
+    // Merge instance attributes with call-time attributes
+    fn merged_attributes(
+        &self,
+        option_user_attributes: &Option<Vec<GrowthBookAttribute>>,
+    ) -> UserAttributes {
+        let mut merged_attributes = Vec::new();
+
+        // Add instance attributes first
+        if let Some(instance_attrs) = &self.attributes {
+            for attr in instance_attrs.values() {
+                merged_attributes.push(attr.clone());
+            }
+        }
+
+        // Add/Override with call-time attributes
+        if let Some(call_attrs) = option_user_attributes {
+            merged_attributes.extend(call_attrs.clone());
+        }
+
+        UserAttributes::new(merged_attributes)
+    }
+}
+
+fn collect_condition_errors(
+    errors: &mut Vec<ConditionError>,
+    location: &str,
+    condition: Option<&Condition>,
+    parent_conditions: Option<&[GrowthBookFeatureRuleParentData]>,
+) {
+    let conditions = condition.map(|condition| (format!("{}.condition", location), condition)).into_iter().chain(
+        parent_conditions
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, parent)| parent.condition().map(|condition| (format!("{}.parentConditions[{}].condition", location, index), condition))),
+    );
+
+    for (location, condition) in conditions {
+        errors.extend(condition.errors().iter().map(|error| error.clone().within(&location)));
     }
 }
 
@@ -65,7 +429,7 @@ mod test {
     use crate::extensions::JsonHelper;
     use crate::growthbook::GrowthBook;
     use crate::model_public::FeatureResult;
-    use crate::model_public::GrowthBookAttribute;
+    use crate::model_public::{Experiment, GrowthBookAttribute};
 
     #[tokio::test]
     async fn evaluate_get_bucket_range() -> Result<(), Box<dyn std::error::Error>> {
@@ -80,9 +444,8 @@ mod test {
             let gb = GrowthBook {
                 forced_variations: feature.forced_variations.clone(),
                 features: gb_test.features.unwrap_or_default(),
-                attributes: None,
-                sticky_bucket_service: None,
                 saved_groups,
+                ..Default::default()
             };
             let user_attributes = feature
                 .attributes
@@ -95,6 +458,96 @@ mod test {
         Ok(())
     }
 
+    // Cached payloads are serialized, so every case must evaluate the same
+    // after a round trip.
+    #[tokio::test]
+    async fn evaluate_feature_after_serialization_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let cases = Cases::new();
+
+        for value in cases.feature {
+            let feature = EvalFeature::new(value);
+
+            let gb_test = serde_json::from_value::<GrowthBookForTest>(feature.feature.clone())?;
+            let serialized = serde_json::to_value(gb_test.features.unwrap_or_default())?;
+            let gb = GrowthBook {
+                forced_variations: feature.forced_variations.clone(),
+                features: serde_json::from_value(serialized)?,
+                saved_groups: saved_groups_from_value(feature.feature.get("savedGroups")),
+                ..Default::default()
+            };
+            let user_attributes = feature
+                .attributes
+                .clone()
+                .map(|attr| GrowthBookAttribute::from(attr).expect("Failed to convert to GrowthBookAttribute"));
+            let result = gb.check(feature.feature_name.as_str(), &user_attributes);
+            validate_result(feature, result);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn evaluate_run() -> Result<(), Box<dyn std::error::Error>> {
+        let cases = Cases::new();
+
+        for value in cases.run {
+            let eval_run = EvalRun::new(value);
+
+            let gb = GrowthBook {
+                forced_variations: eval_run.context.get("forcedVariations").and_then(|it| serde_json::from_value(it.clone()).ok()),
+                features: eval_run.context.get("features").and_then(|it| serde_json::from_value(it.clone()).ok()).unwrap_or_default(),
+                saved_groups: saved_groups_from_value(eval_run.context.get("savedGroups")),
+                enabled: eval_run.context.get("enabled").and_then(Value::as_bool).unwrap_or(true),
+                qa_mode: eval_run.context.get("qaMode").and_then(Value::as_bool).unwrap_or(false),
+                // JS honors query-string overrides by default.
+                query_string_overrides: true,
+                ..Default::default()
+            };
+            // The JS context `url` is the `url` attribute here.
+            let mut attributes = eval_run.context.get("attributes").cloned().unwrap_or(Value::Object(Default::default()));
+            if let (Some(url), Some(map)) = (eval_run.context.get("url"), attributes.as_object_mut()) {
+                map.insert(String::from("url"), url.clone());
+            }
+            let user_attributes = GrowthBookAttribute::from(attributes).ok();
+            let result = gb.run(&eval_run.experiment, &user_attributes);
+
+            let case_name = eval_run.name;
+            assert_eq!(eval_run.value, result.value, "Invalid value for '{case_name}'");
+            assert_eq!(eval_run.in_experiment, result.in_experiment, "Invalid in_experiment for '{case_name}'");
+            assert_eq!(eval_run.hash_used, result.hash_used, "Invalid hash_used for '{case_name}'");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn evaluate_url_redirect() -> Result<(), Box<dyn std::error::Error>> {
+        let cases = Cases::new();
+
+        for value in cases.url_redirect {
+            let eval_redirect = EvalUrlRedirect::new(value);
+            let gb = GrowthBook {
+                experiments: eval_redirect.experiments,
+                ..Default::default()
+            };
+            let user_attributes = GrowthBookAttribute::from(eval_redirect.attributes).ok();
+            let (_, redirect) = gb.run_redirect_experiments(&eval_redirect.url, &user_attributes);
+
+            let case_name = eval_redirect.name;
+            let expected = eval_redirect.result.first().expect("Failed to get expected redirect");
+            let redirect = redirect.unwrap_or_else(|| panic!("Missing redirect for '{case_name}'"));
+            assert_eq!(
+                expected.get_value("inExperiment", Value::Bool(false)).force_bool(false),
+                redirect.experiment_result.in_experiment,
+                "Invalid in_experiment for '{case_name}'"
+            );
+            assert_eq!(expected.get_string("urlRedirect", ""), redirect.url_redirect, "Invalid url_redirect for '{case_name}'");
+            assert_eq!(expected.get_string("urlWithParams", ""), redirect.url_with_params, "Invalid url_with_params for '{case_name}'");
+        }
+
+        Ok(())
+    }
+
     fn validate_result(
         eval_feature: EvalFeature,
         feature_result: FeatureResult,
@@ -120,6 +573,8 @@ mod test {
     #[serde(rename_all = "camelCase")]
     struct Cases {
         feature: Vec<Value>,
+        run: Vec<Value>,
+        url_redirect: Vec<Value>,
     }
 
     #[derive(Deserialize, Clone)]
@@ -162,6 +617,50 @@ mod test {
         }
     }
 
+    pub struct EvalRun {
+        name: String,
+        context: Value,
+        experiment: Experiment,
+        value: Value,
+        in_experiment: bool,
+        hash_used: bool,
+    }
+
+    impl EvalRun {
+        fn new(value: Value) -> Self {
+            let array = value.as_array().expect("Failed to convert to array");
+            Self {
+                name: array[0].as_str().expect("Failed to convert to str").to_string(),
+                context: array[1].clone(),
+                experiment: serde_json::from_value(array[2].clone()).expect("Failed to convert to Experiment"),
+                value: array[3].clone(),
+                in_experiment: array[4].as_bool().expect("Failed to convert to bool"),
+                hash_used: array[5].as_bool().expect("Failed to convert to bool"),
+            }
+        }
+    }
+
+    pub struct EvalUrlRedirect {
+        name: String,
+        attributes: Value,
+        url: String,
+        experiments: Vec<Experiment>,
+        result: Vec<Value>,
+    }
+
+    impl EvalUrlRedirect {
+        fn new(value: Value) -> Self {
+            let array = value.as_array().expect("Failed to convert to array");
+            Self {
+                name: array[0].as_str().expect("Failed to convert to str").to_string(),
+                attributes: array[1].get("attributes").cloned().unwrap_or(Value::Object(Default::default())),
+                url: array[1].get_string("url", ""),
+                experiments: serde_json::from_value(array[1].get("experiments").cloned().unwrap_or_default()).expect("Failed to convert to Experiment"),
+                result: array[2].as_array().expect("Failed to convert to array").clone(),
+            }
+        }
+    }
+
     impl Cases {
         pub fn new() -> Self {
             let contents = fs::read_to_string("./tests/all_cases.json").expect("Should have been able to read the file");
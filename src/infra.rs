@@ -28,8 +28,26 @@ impl HttpClient {
             .build()
             .map_err(GrowthbookError::from)?;
 
+        Self::with_middleware(name, default_config_client)
+    }
+
+    /// A client for long-lived responses (server-sent events): only
+    /// connecting is bounded, since the body never completes.
+    pub fn create_streaming_http_client(
+        #[allow(unused_variables)] name: &str,
+        connect_timeout: Duration,
+    ) -> Result<ClientWithMiddleware, GrowthbookError> {
+        let streaming_client = Client::builder().connect_timeout(connect_timeout).build().map_err(GrowthbookError::from)?;
+
+        Self::with_middleware(name, streaming_client)
+    }
+
+    fn with_middleware(
+        #[allow(unused_variables)] name: &str,
+        client: Client,
+    ) -> Result<ClientWithMiddleware, GrowthbookError> {
         #[allow(unused_mut)]
-        let mut client_builder = ClientBuilder::new(default_config_client);
+        let mut client_builder = ClientBuilder::new(client);
 
         #[cfg(feature = "tracing")]
         {
@@ -5,6 +5,7 @@ pub mod coverage;
 pub mod dto;
 mod env;
 pub mod error;
+mod experiment;
 mod extensions;
 mod feature;
 pub mod filter;
@@ -16,3 +17,5 @@ pub mod model_public;
 mod namespace;
 mod range;
 pub mod sticky_bucket;
+mod streaming;
+pub mod tracking;
@@ -1,9 +1,12 @@
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use std::ops::Deref;
 
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::{Map, Value};
 
+use crate::dto::{Condition, GrowthBookFeatureRuleParentData};
 use crate::error::{GrowthbookError, GrowthbookErrorCode};
 use crate::extensions::JsonHelper;
 
@@ -24,6 +27,15 @@ pub enum GrowthBookAttributeValue {
     Object(Vec<GrowthBookAttribute>),
 }
 
+/// A user's attributes, indexed by key so evaluations don't scan them on
+/// every lookup. When a key appears more than once the first entry wins, as
+/// with a plain `Vec`. Derefs to the attributes in their original order.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct UserAttributes {
+    attributes: Vec<GrowthBookAttribute>,
+    index: HashMap<String, usize>,
+}
+
 #[derive(Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct FeatureResult {
@@ -33,29 +45,191 @@ pub struct FeatureResult {
     pub experiment: Option<Experiment>,
     pub experiment_result: Option<ExperimentResult>,
     pub source: String,
+    /// Exposures a matched force rule asks to track (its `tracks` array),
+    /// fired through `on_experiment_viewed`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tracks: Vec<TrackData>,
 }
 
-#[derive(Serialize, Clone, Debug)]
+/// An experiment definition. Produced for experiment rules on a
+/// `FeatureResult`, and accepted as input by `GrowthBookClient::run` for
+/// inline experiments (the JS `Experiment` shape). Build one with
+/// `Experiment::new` and set the other fields on it; it is non-exhaustive so
+/// that fields can be added without breaking callers.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct Experiment {
+    pub key: String,
     pub name: Option<String>,
     pub seed: Option<String>,
     pub hash_version: Option<i64>,
     pub hash_attribute: Option<String>,
+    pub fallback_attribute: Option<String>,
     pub namespace: Option<Vec<Value>>,
     pub coverage: Option<f32>,
     pub ranges: Option<Vec<Vec<f32>>>,
     pub meta: Option<Value>,
     pub filters: Option<Value>,
+    #[serde(default)]
     pub variations: Vec<Value>,
     pub weights: Option<Vec<f32>>,
-    pub condition: Option<Value>,
+    /// Compiled when the experiment is deserialized; build one in code with
+    /// `Condition::compile`.
+    pub condition: Option<Condition>,
+    pub parent_conditions: Option<Vec<GrowthBookFeatureRuleParentData>>,
+    /// `Some(false)` skips the experiment for everyone.
+    pub active: Option<bool>,
+    /// Serve this variation index to every bucketed user.
+    pub force: Option<i64>,
+    /// Only users on a matching URL (the `url` attribute) are eligible.
+    pub url_patterns: Option<Vec<UrlPattern>>,
+    /// Carry the current URL's query string over to a `urlRedirect`.
+    pub persist_query_string: Option<bool>,
+    /// Manual experiments are never run automatically (e.g. for redirects).
+    pub manual: Option<bool>,
+}
+
+impl Experiment {
+    /// An experiment splitting users evenly between `variations`.
+    pub fn new(
+        key: impl Into<String>,
+        variations: Vec<Value>,
+    ) -> Self {
+        Self {
+            key: key.into(),
+            variations,
+            ..Default::default()
+        }
+    }
+
+    /// The condition as written in the payload, before it was compiled.
+    pub fn condition_source(&self) -> Option<&Value> {
+        self.condition.as_ref().map(Condition::source)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlPattern {
+    #[serde(rename = "type")]
+    pub kind: UrlPatternType,
+    /// `Some(false)` excludes matching URLs; anything else includes them.
+    pub include: Option<bool>,
+    pub pattern: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum UrlPatternType {
+    Simple,
+    Regex,
+    /// A pattern type this SDK doesn't know; never matches.
+    #[serde(other)]
+    Unknown,
+}
+
+/// `GrowthBookClient::explain_feature`: the result plus every rule visited on
+/// the way to it, in evaluation order. Rules of prerequisite and holdout
+/// features appear right after the rule that evaluated them.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureExplanation {
+    pub result: FeatureResult,
+    pub trace: Vec<RuleTrace>,
 }
 
 #[derive(Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+pub struct RuleTrace {
+    /// The feature the rule belongs to.
+    pub feature_id: String,
+    pub rule_index: usize,
+    pub rule_id: Option<String>,
+    pub kind: RuleTraceKind,
+    pub outcome: RuleOutcome,
+    pub hash_attribute: Option<String>,
+    pub hash_value: Option<Value>,
+    /// The hashed weight the rule compared against its coverage or ranges.
+    pub bucket: Option<f32>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleTraceKind {
+    Force,
+    Rollout,
+    Experiment,
+    Empty,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleOutcome {
+    /// The rule produced the result.
+    Matched,
+    ConditionFailed,
+    /// Excluded by the rule's `filters`.
+    FilteredOut,
+    /// The bucket is outside the rule's coverage or ranges.
+    NotInCoverage,
+    NotInNamespace,
+    /// The user has no value for the hash attribute.
+    MissingHashAttribute,
+    /// A non-gating prerequisite failed, so the rule was skipped.
+    ParentSkipped,
+    /// A gating (or cyclic) prerequisite failed and ended evaluation.
+    ParentBlocked,
+    /// The experiment assigned a passthrough variation.
+    Passthrough,
+    /// Experiments are disabled, or QA mode is on without a forced variation.
+    ExperimentsDisabled,
+    /// A sticky bucket below `minBucketVersion` unenrolled the user.
+    StickyBucketBlocked,
+    /// A rule with nothing to serve.
+    NotMatched,
+}
+
+/// An exposure embedded in a force rule's `tracks` array (holdouts, safe
+/// rollouts). The result is reported as-is when the rule matches.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackData {
+    pub experiment: Experiment,
+    pub result: ExperimentResult,
+}
+
+impl TrackData {
+    /// The result to hand to `on_experiment_viewed`. Payload results don't
+    /// carry the experiment key, so it is taken from the experiment.
+    pub fn experiment_result(&self) -> ExperimentResult {
+        let mut result = self.result.clone();
+        if result.experiment_key.is_empty() {
+            result.experiment_key = self.experiment.key.clone();
+        }
+        result
+    }
+}
+
+/// A URL redirect to perform for a split-URL experiment.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RedirectResult {
+    /// The assigned variation's `urlRedirect`.
+    pub url_redirect: String,
+    /// Where to send the user: `url_redirect`, plus the current query string
+    /// when the experiment persists it.
+    pub url_with_params: String,
+    pub experiment_result: ExperimentResult,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
 pub struct ExperimentResult {
+    /// The feature the experiment rule belongs to; empty for inline experiments.
+    #[serde(deserialize_with = "null_as_default")]
     pub feature_id: String,
+    pub experiment_key: String,
     pub value: Value,
     pub variation_id: i64,
     pub in_experiment: bool,
@@ -67,6 +241,16 @@ pub struct ExperimentResult {
     pub sticky_bucket_used: bool,
 }
 
+// JS sends `null` where it has no value (e.g. `featureId` outside a
+// feature), and `#[serde(default)]` only covers missing fields.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 impl GrowthBookAttribute {
     pub fn new(
         key: String,
@@ -96,6 +280,38 @@ impl GrowthBookAttribute {
     }
 }
 
+impl UserAttributes {
+    pub fn new(attributes: Vec<GrowthBookAttribute>) -> Self {
+        let mut index = HashMap::with_capacity(attributes.len());
+        for (position, attribute) in attributes.iter().enumerate() {
+            index.entry(attribute.key.clone()).or_insert(position);
+        }
+        Self { attributes, index }
+    }
+
+    /// The first attribute named `key`.
+    pub fn get(
+        &self,
+        key: &str,
+    ) -> Option<&GrowthBookAttribute> {
+        self.index.get(key).map(|position| &self.attributes[*position])
+    }
+}
+
+impl From<Vec<GrowthBookAttribute>> for UserAttributes {
+    fn from(attributes: Vec<GrowthBookAttribute>) -> Self {
+        Self::new(attributes)
+    }
+}
+
+impl Deref for UserAttributes {
+    type Target = [GrowthBookAttribute];
+
+    fn deref(&self) -> &[GrowthBookAttribute] {
+        &self.attributes
+    }
+}
+
 impl GrowthBookAttributeValue {
     pub fn is_number(&self) -> bool {
         if let Ok(regex) = Regex::new("\\d+") {
@@ -213,6 +429,7 @@ impl FeatureResult {
             experiment: None,
             experiment_result: None,
             source,
+            tracks: Vec::new(),
         }
     }
 
@@ -225,6 +442,21 @@ impl FeatureResult {
             experiment: None,
             experiment_result: None,
             source: String::from("force"),
+            tracks: Vec::new(),
+        }
+    }
+
+    /// A value pinned through the client's forced features.
+    pub fn forced_feature(value: Value) -> Self {
+        let is_on = is_on(&value);
+        FeatureResult {
+            value,
+            on: is_on,
+            off: !is_on,
+            experiment: None,
+            experiment_result: None,
+            source: String::from("override"),
+            tracks: Vec::new(),
         }
     }
 
@@ -241,6 +473,26 @@ impl FeatureResult {
             experiment: Some(experiment),
             experiment_result: Some(experiment_result),
             source: String::from("experiment"),
+            tracks: Vec::new(),
+        }
+    }
+
+    /// A user held out of the feature by its holdout. Carries the holdout
+    /// experiment so the exposure is tracked.
+    pub fn holdout(
+        value: Value,
+        experiment: Experiment,
+        experiment_result: ExperimentResult,
+    ) -> Self {
+        let is_on = is_on(&value);
+        FeatureResult {
+            value,
+            on: is_on,
+            off: !is_on,
+            experiment: Some(experiment),
+            experiment_result: Some(experiment_result),
+            source: String::from("holdout"),
+            tracks: Vec::new(),
         }
     }
 
@@ -254,6 +506,7 @@ impl FeatureResult {
             experiment: None,
             experiment_result: None,
             source: String::from("defaultValue"),
+            tracks: Vec::new(),
         }
     }
 
@@ -265,6 +518,7 @@ impl FeatureResult {
             experiment: None,
             experiment_result: None,
             source: String::from("prerequisite"),
+            tracks: Vec::new(),
         }
     }
 
@@ -276,6 +530,7 @@ impl FeatureResult {
             experiment: None,
             experiment_result: None,
             source: String::from("cyclicPrerequisite"),
+            tracks: Vec::new(),
         }
     }
 
@@ -287,6 +542,7 @@ impl FeatureResult {
             experiment: None,
             experiment_result: None,
             source: String::from("unknownFeature"),
+            tracks: Vec::new(),
         }
     }
 }
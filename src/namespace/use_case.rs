@@ -1,3 +1,6 @@
+use serde_json::Value;
+
+use crate::extensions::JsonHelper;
 use crate::hash::{HashCode, HashCodeVersion};
 use crate::model_public::GrowthBookAttributeValue;
 use crate::range::model::Range;
@@ -13,6 +16,26 @@ impl Namespace {
         let user_weight = HashCode::hash_code(&format!("{}__", user_value), namespace, HashCodeVersion::from(1)).unwrap_or(-1.0);
         range.in_range(&user_weight)
     }
+
+    /// Splits a `[id, start, end]` namespace tuple into its id and range.
+    pub fn get_range(namespace: Option<&Vec<Value>>) -> Option<(String, Range)> {
+        namespace.map(|namespace| {
+            // #18: a malformed namespace tuple (fewer than 3 elements) must not
+            // panic. JS `inNamespace` compares the hash against `undefined` and
+            // returns false, so the user is excluded — represent that as an empty
+            // range (start >= end) that `Range::in_range` never matches.
+            match (namespace.first(), namespace.get(1), namespace.get(2)) {
+                (Some(id), Some(start), Some(end)) => (
+                    id.force_string(""),
+                    Range {
+                        start: start.force_f32(0.0),
+                        end: end.force_f32(1.0),
+                    },
+                ),
+                _ => (String::new(), Range { start: 1.0, end: 0.0 }),
+            }
+        })
+    }
 }
 
 #[cfg(test)]
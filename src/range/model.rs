@@ -16,6 +16,30 @@ impl Range {
         }
     }
 
+    /// The experiment's explicit `ranges` when present, otherwise the bucket
+    /// ranges derived from its variation count, coverage and weights.
+    pub fn get_ranges(
+        ranges: Option<&Vec<Vec<f32>>>,
+        variations: i64,
+        coverage: &Option<f32>,
+        weights: Option<Vec<f32>>,
+    ) -> Vec<Self> {
+        if let Some(ranges) = ranges {
+            // #18: a malformed tuple with fewer than two elements must not panic.
+            // A degenerate range (start >= end) matches nobody, mirroring JS where
+            // `n < undefined` is false.
+            ranges
+                .iter()
+                .map(|range| Range {
+                    start: range.first().copied().unwrap_or(0.0),
+                    end: range.get(1).copied().unwrap_or(0.0),
+                })
+                .collect()
+        } else {
+            Self::get_bucket_range(variations, coverage, weights)
+        }
+    }
+
     pub fn get_bucket_range(
         variations: i64,
         coverage: &Option<f32>,
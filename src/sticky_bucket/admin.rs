@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use crate::error::GrowthbookError;
+use crate::sticky_bucket::{InMemoryStickyBucketService, StickyAssignmentsDocument, StickyBucketService};
+
+/// The `"{experiment_key}__{bucket_version}"` key of an assignment.
+pub fn assignment_key(
+    experiment_key: &str,
+    bucket_version: i64,
+) -> String {
+    format!("{}__{}", experiment_key, bucket_version)
+}
+
+/// The experiment key and bucket version of an assignment key; `None` when
+/// it doesn't end in `__{bucket_version}`.
+pub fn parse_assignment_key(key: &str) -> Option<(&str, i64)> {
+    let (experiment_key, bucket_version) = key.rsplit_once("__")?;
+    Some((experiment_key, bucket_version.parse().ok()?))
+}
+
+/// Maintenance operations on a sticky bucket store, for tooling rather than
+/// evaluation: listing, bulk import and purging assignments.
+pub trait StickyBucketAdmin: StickyBucketService {
+    /// Every stored document.
+    fn all_documents(&self) -> Result<Vec<StickyAssignmentsDocument>, GrowthbookError>;
+
+    /// Stores `documents`, replacing the documents with the same keys.
+    fn import_documents(
+        &self,
+        documents: Vec<StickyAssignmentsDocument>,
+    ) -> Result<(), GrowthbookError>;
+
+    /// Removes the assignments whose key matches `predicate`, and the
+    /// documents left empty. Returns how many assignments were removed.
+    fn remove_assignments(
+        &self,
+        predicate: &dyn Fn(&str) -> bool,
+    ) -> Result<usize, GrowthbookError>;
+
+    /// Every document as a JSON array, sorted by key, in the JS document
+    /// format.
+    fn export_json(&self) -> Result<String, GrowthbookError> {
+        let mut documents = self.all_documents()?;
+        documents.sort_by_key(|doc| doc.key());
+        Ok(serde_json::to_string(&documents)?)
+    }
+
+    /// Imports a JSON array of documents, as written by `export_json`.
+    /// Returns how many documents were imported.
+    fn import_json(
+        &self,
+        json: &str,
+    ) -> Result<usize, GrowthbookError> {
+        let documents: Vec<StickyAssignmentsDocument> = serde_json::from_str(json)?;
+        let imported = documents.len();
+        self.import_documents(documents)?;
+        Ok(imported)
+    }
+
+    /// Removes every assignment of `experiment_key`, whatever its bucket
+    /// version.
+    fn delete_experiment(
+        &self,
+        experiment_key: &str,
+    ) -> Result<usize, GrowthbookError> {
+        self.remove_assignments(&|key| parse_assignment_key(key).is_some_and(|(experiment, _)| experiment == experiment_key))
+    }
+
+    /// Removes the assignments of `experiment_key` with a bucket version
+    /// below `min_bucket_version`. Evaluation keeps a user holding one of
+    /// them out of the experiment (`StickyBucketBlocked`), so deleting them
+    /// re-enrolls those users, who are bucketed again.
+    fn delete_versions_below(
+        &self,
+        experiment_key: &str,
+        min_bucket_version: i64,
+    ) -> Result<usize, GrowthbookError> {
+        self.remove_assignments(&|key| parse_assignment_key(key).is_some_and(|(experiment, version)| experiment == experiment_key && version < min_bucket_version))
+    }
+
+    /// How many users hold each variation of the experiment at
+    /// `bucket_version`, counting the documents of `attribute_name` (the
+    /// experiment's hash attribute). Assignments stored under a fallback
+    /// attribute are also copied to the hash attribute once it is known, so
+    /// counting every document would count those users twice.
+    fn variation_counts(
+        &self,
+        experiment_key: &str,
+        bucket_version: i64,
+        attribute_name: &str,
+    ) -> Result<BTreeMap<String, usize>, GrowthbookError> {
+        let key = assignment_key(experiment_key, bucket_version);
+        let mut counts = BTreeMap::new();
+        for doc in self.all_documents()?.into_iter().filter(|doc| doc.attribute_name == attribute_name) {
+            if let Some(variation) = doc.assignments.get(&key) {
+                *counts.entry(variation.clone()).or_insert(0) += 1;
+            }
+        }
+        Ok(counts)
+    }
+}
+
+/// Copies every document of `from` into `to`, e.g. to migrate between
+/// backends. Returns how many documents were copied.
+pub fn copy_documents(
+    from: &dyn StickyBucketAdmin,
+    to: &dyn StickyBucketAdmin,
+) -> Result<usize, GrowthbookError> {
+    let documents = from.all_documents()?;
+    let copied = documents.len();
+    to.import_documents(documents)?;
+    Ok(copied)
+}
+
+impl StickyBucketAdmin for InMemoryStickyBucketService {
+    fn all_documents(&self) -> Result<Vec<StickyAssignmentsDocument>, GrowthbookError> {
+        Ok(self.storage.read().unwrap().values().cloned().collect())
+    }
+
+    fn import_documents(
+        &self,
+        documents: Vec<StickyAssignmentsDocument>,
+    ) -> Result<(), GrowthbookError> {
+        let mut storage = self.storage.write().unwrap();
+        storage.extend(documents.into_iter().map(|doc| (doc.key(), doc)));
+        Ok(())
+    }
+
+    fn remove_assignments(
+        &self,
+        predicate: &dyn Fn(&str) -> bool,
+    ) -> Result<usize, GrowthbookError> {
+        let mut storage = self.storage.write().unwrap();
+        let removed = remove_matching(storage.values_mut(), predicate);
+        storage.retain(|_, doc| !doc.assignments.is_empty());
+        Ok(removed)
+    }
+}
+
+/// Removes the assignments matching `predicate` from `documents`, returning
+/// how many were removed.
+pub(crate) fn remove_matching<'a>(
+    documents: impl Iterator<Item = &'a mut StickyAssignmentsDocument>,
+    predicate: &dyn Fn(&str) -> bool,
+) -> usize {
+    let mut removed = 0;
+    for doc in documents {
+        let before = doc.assignments.len();
+        doc.assignments.retain(|key, _| !predicate(key));
+        removed += before - doc.assignments.len();
+    }
+    removed
+}
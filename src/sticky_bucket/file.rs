@@ -0,0 +1,169 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+
+use fs4::FileExt;
+#[cfg(feature = "tracing")]
+use tracing::error;
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! error {
+    ($($arg:tt)*) => {{
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+use crate::cache::temp_path;
+use crate::error::{GrowthbookError, GrowthbookErrorCode};
+use crate::sticky_bucket::admin::remove_matching;
+use crate::sticky_bucket::{sticky_attribute_key, StickyAssignmentsDocument, StickyBucketAdmin, StickyBucketService};
+
+/// Sticky bucket documents persisted in a single JSON file, an object of
+/// documents by `key()` (the shape JS keeps in `localStorage`).
+///
+/// Writes go through a temporary file and a rename, so readers never see a
+/// partial file. Writers, in this or other processes, take an exclusive lock
+/// on `{path}.lock` and merge their assignments into the stored document, so
+/// concurrent writers of different experiments don't overwrite each other.
+#[derive(Debug)]
+pub struct JsonFileStickyBucketService {
+    path: PathBuf,
+}
+
+impl JsonFileStickyBucketService {
+    /// Documents are stored in the file at `path`, whose directory is created
+    /// on first write.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        let mut lock_path = OsString::from(self.path.as_os_str());
+        lock_path.push(".lock");
+        PathBuf::from(lock_path)
+    }
+
+    fn read(&self) -> io::Result<BTreeMap<String, StickyAssignmentsDocument>> {
+        match fs::read(&self.path) {
+            Ok(contents) => serde_json::from_slice(&contents).map_err(io::Error::from),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn write(
+        &self,
+        documents: &BTreeMap<String, StickyAssignmentsDocument>,
+    ) -> io::Result<()> {
+        let temp_path = temp_path(&self.path);
+        if let Err(e) = serde_json::to_vec(documents).map_err(io::Error::from).and_then(|contents| fs::write(&temp_path, contents)) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
+        fs::rename(&temp_path, &self.path)
+    }
+
+    // Read-modify-write under the lock. An unreadable file is left alone
+    // rather than replaced.
+    fn update<T>(
+        &self,
+        modify: impl FnOnce(&mut BTreeMap<String, StickyAssignmentsDocument>) -> T,
+    ) -> io::Result<T> {
+        if let Some(directory) = self.path.parent().filter(|it| !it.as_os_str().is_empty()) {
+            fs::create_dir_all(directory)?;
+        }
+        let lock = OpenOptions::new().create(true).truncate(false).write(true).open(self.lock_path())?;
+        lock.lock_exclusive()?;
+
+        let result = self.read().and_then(|mut documents| {
+            let value = modify(&mut documents);
+            self.write(&documents).map(|_| value)
+        });
+
+        // Qualified: std's own `File::unlock` is newer than the MSRV.
+        let _ = FileExt::unlock(&lock);
+        result
+    }
+
+    fn save(
+        &self,
+        doc: StickyAssignmentsDocument,
+    ) -> io::Result<()> {
+        self.update(|documents| {
+            let stored = documents
+                .entry(doc.key())
+                .or_insert_with(|| StickyAssignmentsDocument::new(&doc.attribute_name, &doc.attribute_value, HashMap::new()));
+            stored.assignments.extend(doc.assignments);
+        })
+    }
+
+    fn read_or_log(&self) -> BTreeMap<String, StickyAssignmentsDocument> {
+        self.read().unwrap_or_else(|e| {
+            error!("[growthbook-sdk] Failed to read sticky bucket file {:?}: {:?}", self.path, e);
+            BTreeMap::new()
+        })
+    }
+}
+
+impl StickyBucketService for JsonFileStickyBucketService {
+    fn get_assignments(
+        &self,
+        attribute_name: &str,
+        attribute_value: &str,
+    ) -> Option<StickyAssignmentsDocument> {
+        self.read_or_log().remove(&sticky_attribute_key(attribute_name, attribute_value))
+    }
+
+    /// Merges `doc`'s assignments into the stored document.
+    fn save_assignments(
+        &self,
+        doc: StickyAssignmentsDocument,
+    ) {
+        if let Err(e) = self.save(doc) {
+            error!("[growthbook-sdk] Failed to write sticky bucket file {:?}: {:?}", self.path, e);
+        }
+    }
+
+    fn get_all_assignments(
+        &self,
+        attributes: &HashMap<String, String>,
+    ) -> HashMap<String, StickyAssignmentsDocument> {
+        let mut documents = self.read_or_log();
+        attributes
+            .iter()
+            .filter_map(|(name, value)| documents.remove(&sticky_attribute_key(name, value)))
+            .map(|doc| (doc.key(), doc))
+            .collect()
+    }
+}
+
+impl StickyBucketAdmin for JsonFileStickyBucketService {
+    fn all_documents(&self) -> Result<Vec<StickyAssignmentsDocument>, GrowthbookError> {
+        Ok(self.read().map_err(storage_error)?.into_values().collect())
+    }
+
+    fn import_documents(
+        &self,
+        documents: Vec<StickyAssignmentsDocument>,
+    ) -> Result<(), GrowthbookError> {
+        self.update(|stored| stored.extend(documents.into_iter().map(|doc| (doc.key(), doc)))).map_err(storage_error)
+    }
+
+    fn remove_assignments(
+        &self,
+        predicate: &dyn Fn(&str) -> bool,
+    ) -> Result<usize, GrowthbookError> {
+        self.update(|documents| {
+            let removed = remove_matching(documents.values_mut(), predicate);
+            documents.retain(|_, doc| !doc.assignments.is_empty());
+            removed
+        })
+        .map_err(storage_error)
+    }
+}
+
+fn storage_error(error: io::Error) -> GrowthbookError {
+    GrowthbookError::new(GrowthbookErrorCode::StickyBucketStorage, &error.to_string())
+}
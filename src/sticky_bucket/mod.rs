@@ -0,0 +1,274 @@
+use crate::cache::BoxFuture;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
+
+mod admin;
+#[cfg(feature = "sticky-bucket-file")]
+mod file;
+#[cfg(feature = "sticky-bucket-sqlite")]
+mod sqlite;
+
+pub use admin::{assignment_key, copy_documents, parse_assignment_key, StickyBucketAdmin};
+#[cfg(feature = "sticky-bucket-file")]
+pub use file::JsonFileStickyBucketService;
+#[cfg(feature = "sticky-bucket-sqlite")]
+pub use sqlite::SqliteStickyBucketService;
+
+/// The sticky assignments of one attribute value, in the format shared with
+/// the JS and Python SDKs:
+/// `{"attributeName": "id", "attributeValue": "123", "assignments": {"exp__0": "1"}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StickyAssignmentsDocument {
+    pub attribute_name: String,
+    pub attribute_value: String,
+    /// `{experiment_key}__{bucket_version}` -> variation index, serialized
+    /// in key order so dumps are stable.
+    #[serde(serialize_with = "serialize_sorted")]
+    pub assignments: HashMap<String, String>,
+}
+
+fn serialize_sorted<S: Serializer>(
+    assignments: &HashMap<String, String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    assignments.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+impl StickyAssignmentsDocument {
+    pub fn new(
+        attribute_name: impl Into<String>,
+        attribute_value: impl Into<String>,
+        assignments: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            attribute_name: attribute_name.into(),
+            attribute_value: attribute_value.into(),
+            assignments,
+        }
+    }
+
+    /// The `"{attribute_name}||{attribute_value}"` key the document is
+    /// stored under.
+    pub fn key(&self) -> String {
+        sticky_attribute_key(&self.attribute_name, &self.attribute_value)
+    }
+
+    /// Converts an entry stored in the previous format, assignments keyed by
+    /// `"{attribute_name}||{attribute_value}"`. `None` if the key has no `||`.
+    pub fn from_legacy(
+        key: &str,
+        assignments: HashMap<String, String>,
+    ) -> Option<Self> {
+        let (attribute_name, attribute_value) = key.split_once("||")?;
+        Some(Self::new(attribute_name, attribute_value, assignments))
+    }
+
+    /// The inverse of `from_legacy`.
+    pub fn into_legacy(self) -> (String, HashMap<String, String>) {
+        (self.key(), self.assignments)
+    }
+}
+
+pub fn sticky_attribute_key(
+    attribute_name: &str,
+    attribute_value: &str,
+) -> String {
+    format!("{}||{}", attribute_name, attribute_value)
+}
+
+pub trait StickyBucketService: Send + Sync + Debug {
+    fn get_assignments(
+        &self,
+        attribute_name: &str,
+        attribute_value: &str,
+    ) -> Option<StickyAssignmentsDocument>;
+    /// Stores `doc`, replacing the document with the same key.
+    fn save_assignments(
+        &self,
+        doc: StickyAssignmentsDocument,
+    );
+    /// The documents for each attribute name -> value pair, by `key()`.
+    fn get_all_assignments(
+        &self,
+        attributes: &HashMap<String, String>,
+    ) -> HashMap<String, StickyAssignmentsDocument> {
+        attributes.iter().filter_map(|(name, value)| self.get_assignments(name, value)).map(|doc| (doc.key(), doc)).collect()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryStickyBucketService {
+    // Key: $"{attribute_name}||${attribute_value}"
+    storage: Arc<RwLock<HashMap<String, StickyAssignmentsDocument>>>,
+}
+
+impl InMemoryStickyBucketService {
+    pub fn new() -> Self {
+        Self {
+            storage: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn from_documents(documents: impl IntoIterator<Item = StickyAssignmentsDocument>) -> Self {
+        Self {
+            storage: Arc::new(RwLock::new(documents.into_iter().map(|doc| (doc.key(), doc)).collect())),
+        }
+    }
+}
+
+impl StickyBucketService for InMemoryStickyBucketService {
+    fn get_assignments(
+        &self,
+        attribute_name: &str,
+        attribute_value: &str,
+    ) -> Option<StickyAssignmentsDocument> {
+        let storage = self.storage.read().unwrap();
+        storage.get(&sticky_attribute_key(attribute_name, attribute_value)).cloned()
+    }
+
+    fn save_assignments(
+        &self,
+        doc: StickyAssignmentsDocument,
+    ) {
+        let mut storage = self.storage.write().unwrap();
+        storage.insert(doc.key(), doc);
+    }
+}
+
+/// Sticky bucket storage that is read and written asynchronously (a
+/// database, a KV store). The client prefetches every document a user needs
+/// before evaluating, evaluates against them in memory, then saves the
+/// documents that changed, like JS `refreshStickyBuckets`.
+pub trait AsyncStickyBucketService: Send + Sync + Debug {
+    /// The documents for each attribute name -> value pair, by `key()`;
+    /// pairs without a document are left out.
+    fn get_all_assignments<'a>(
+        &'a self,
+        attributes: &'a HashMap<String, String>,
+    ) -> BoxFuture<'a, HashMap<String, StickyAssignmentsDocument>>;
+    fn save_assignments(
+        &self,
+        doc: StickyAssignmentsDocument,
+    ) -> BoxFuture<'_, ()>;
+}
+
+impl AsyncStickyBucketService for InMemoryStickyBucketService {
+    fn get_all_assignments<'a>(
+        &'a self,
+        attributes: &'a HashMap<String, String>,
+    ) -> BoxFuture<'a, HashMap<String, StickyAssignmentsDocument>> {
+        Box::pin(async move { StickyBucketService::get_all_assignments(self, attributes) })
+    }
+
+    fn save_assignments(
+        &self,
+        doc: StickyAssignmentsDocument,
+    ) -> BoxFuture<'_, ()> {
+        Box::pin(async move { StickyBucketService::save_assignments(self, doc) })
+    }
+}
+
+/// Reads sticky buckets from another service and drops every save, so an
+/// evaluation that only looks (e.g. `explain_feature`) enrolls nobody.
+#[derive(Debug)]
+pub(crate) struct ReadOnlyStickyBuckets {
+    inner: Arc<dyn StickyBucketService>,
+}
+
+impl ReadOnlyStickyBuckets {
+    pub(crate) fn wrap(inner: Arc<dyn StickyBucketService>) -> Arc<dyn StickyBucketService> {
+        Arc::new(Self { inner })
+    }
+}
+
+impl StickyBucketService for ReadOnlyStickyBuckets {
+    fn get_assignments(
+        &self,
+        attribute_name: &str,
+        attribute_value: &str,
+    ) -> Option<StickyAssignmentsDocument> {
+        self.inner.get_assignments(attribute_name, attribute_value)
+    }
+
+    fn save_assignments(
+        &self,
+        _doc: StickyAssignmentsDocument,
+    ) {
+    }
+
+    fn get_all_assignments(
+        &self,
+        attributes: &HashMap<String, String>,
+    ) -> HashMap<String, StickyAssignmentsDocument> {
+        self.inner.get_all_assignments(attributes)
+    }
+}
+
+/// The documents prefetched from an `AsyncStickyBucketService` for one
+/// evaluation. Evaluation reads and writes them synchronously; the ones that
+/// changed are then flushed back.
+#[derive(Debug, Default)]
+pub struct PrefetchedStickyBuckets {
+    documents: RwLock<HashMap<String, StickyAssignmentsDocument>>,
+    changed: RwLock<HashSet<String>>,
+}
+
+impl PrefetchedStickyBuckets {
+    pub fn new(documents: HashMap<String, StickyAssignmentsDocument>) -> Self {
+        Self {
+            documents: RwLock::new(documents),
+            changed: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// The documents that were modified since they were prefetched.
+    pub fn changed_documents(&self) -> Vec<StickyAssignmentsDocument> {
+        let documents = self.documents.read().unwrap();
+        let changed = self.changed.read().unwrap();
+        changed.iter().filter_map(|key| documents.get(key).cloned()).collect()
+    }
+
+    /// Saves the changed documents to `service`. Flushing again only saves
+    /// the documents changed since.
+    pub async fn flush(
+        &self,
+        service: &dyn AsyncStickyBucketService,
+    ) {
+        let changed_documents: Vec<_> = {
+            let documents = self.documents.read().unwrap();
+            let mut changed = self.changed.write().unwrap();
+            changed.drain().filter_map(|key| documents.get(&key).cloned()).collect()
+        };
+        for doc in changed_documents {
+            service.save_assignments(doc).await;
+        }
+    }
+}
+
+impl StickyBucketService for PrefetchedStickyBuckets {
+    fn get_assignments(
+        &self,
+        attribute_name: &str,
+        attribute_value: &str,
+    ) -> Option<StickyAssignmentsDocument> {
+        let documents = self.documents.read().unwrap();
+        documents.get(&sticky_attribute_key(attribute_name, attribute_value)).cloned()
+    }
+
+    fn save_assignments(
+        &self,
+        doc: StickyAssignmentsDocument,
+    ) {
+        let key = doc.key();
+        let mut documents = self.documents.write().unwrap();
+
+        // Only a real change needs to be written back.
+        if documents.get(&key) != Some(&doc) {
+            documents.insert(key.clone(), doc);
+            self.changed.write().unwrap().insert(key);
+        }
+    }
+}
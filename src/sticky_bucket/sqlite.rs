@@ -0,0 +1,195 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rusqlite::{params, Connection, Transaction, TransactionBehavior};
+#[cfg(feature = "tracing")]
+use tracing::error;
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! error {
+    ($($arg:tt)*) => {{
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+use crate::error::GrowthbookError;
+use crate::sticky_bucket::{assignment_key, StickyAssignmentsDocument, StickyBucketAdmin, StickyBucketService};
+
+// How long a writer waits for another connection's write to finish.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sticky bucket documents persisted in an embedded SQLite database, one row
+/// per assignment in the `growthbook_sticky_assignments` table.
+///
+/// Saves upsert their assignments in one transaction, so concurrent writers
+/// (other connections or processes sharing the file) never lose each other's
+/// assignments.
+#[derive(Debug)]
+pub struct SqliteStickyBucketService {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStickyBucketService {
+    /// Opens (or creates) the database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GrowthbookError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, GrowthbookError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(connection: Connection) -> Result<Self, GrowthbookError> {
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        // Readers don't block the writer; in-memory databases keep "memory".
+        connection.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))?;
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS growthbook_sticky_assignments (
+                attribute_name TEXT NOT NULL,
+                attribute_value TEXT NOT NULL,
+                assignment_key TEXT NOT NULL,
+                variation TEXT NOT NULL,
+                PRIMARY KEY (attribute_name, attribute_value, assignment_key)
+            )",
+            [],
+        )?;
+        Ok(Self { connection: Mutex::new(connection) })
+    }
+
+    fn get(
+        &self,
+        attribute_name: &str,
+        attribute_value: &str,
+    ) -> rusqlite::Result<HashMap<String, String>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare_cached("SELECT assignment_key, variation FROM growthbook_sticky_assignments WHERE attribute_name = ?1 AND attribute_value = ?2")?;
+        let rows = statement.query_map(params![attribute_name, attribute_value], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    // Immediate: take the write lock up front instead of failing to upgrade
+    // a read lock another writer is waiting on.
+    fn write<T>(
+        &self,
+        write: impl FnOnce(&Transaction) -> rusqlite::Result<T>,
+    ) -> rusqlite::Result<T> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let value = write(&transaction)?;
+        transaction.commit()?;
+        Ok(value)
+    }
+
+    fn save(
+        &self,
+        doc: &StickyAssignmentsDocument,
+    ) -> rusqlite::Result<()> {
+        self.write(|transaction| upsert(transaction, doc))
+    }
+}
+
+fn upsert(
+    transaction: &Transaction,
+    doc: &StickyAssignmentsDocument,
+) -> rusqlite::Result<()> {
+    let mut statement = transaction.prepare_cached(
+        "INSERT INTO growthbook_sticky_assignments (attribute_name, attribute_value, assignment_key, variation) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (attribute_name, attribute_value, assignment_key) DO UPDATE SET variation = excluded.variation",
+    )?;
+    for (assignment_key, variation) in &doc.assignments {
+        statement.execute(params![doc.attribute_name, doc.attribute_value, assignment_key, variation])?;
+    }
+    Ok(())
+}
+
+impl StickyBucketService for SqliteStickyBucketService {
+    fn get_assignments(
+        &self,
+        attribute_name: &str,
+        attribute_value: &str,
+    ) -> Option<StickyAssignmentsDocument> {
+        match self.get(attribute_name, attribute_value) {
+            Ok(assignments) if assignments.is_empty() => None,
+            Ok(assignments) => Some(StickyAssignmentsDocument::new(attribute_name, attribute_value, assignments)),
+            Err(e) => {
+                error!("[growthbook-sdk] Failed to read sticky buckets for '{}': {:?}", attribute_name, e);
+                None
+            },
+        }
+    }
+
+    /// Merges `doc`'s assignments into the stored document.
+    fn save_assignments(
+        &self,
+        doc: StickyAssignmentsDocument,
+    ) {
+        if let Err(e) = self.save(&doc) {
+            error!("[growthbook-sdk] Failed to save sticky buckets for '{}': {:?}", doc.key(), e);
+        }
+    }
+}
+
+impl StickyBucketAdmin for SqliteStickyBucketService {
+    fn all_documents(&self) -> Result<Vec<StickyAssignmentsDocument>, GrowthbookError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare("SELECT attribute_name, attribute_value, assignment_key, variation FROM growthbook_sticky_assignments")?;
+        let mut rows = statement.query([])?;
+        let mut documents: HashMap<(String, String), StickyAssignmentsDocument> = HashMap::new();
+        while let Some(row) = rows.next()? {
+            let (attribute_name, attribute_value): (String, String) = (row.get(0)?, row.get(1)?);
+            documents
+                .entry((attribute_name.clone(), attribute_value.clone()))
+                .or_insert_with(|| StickyAssignmentsDocument::new(attribute_name, attribute_value, HashMap::new()))
+                .assignments
+                .insert(row.get(2)?, row.get(3)?);
+        }
+        Ok(documents.into_values().collect())
+    }
+
+    fn import_documents(
+        &self,
+        documents: Vec<StickyAssignmentsDocument>,
+    ) -> Result<(), GrowthbookError> {
+        Ok(self.write(|transaction| {
+            for doc in &documents {
+                transaction.execute(
+                    "DELETE FROM growthbook_sticky_assignments WHERE attribute_name = ?1 AND attribute_value = ?2",
+                    params![doc.attribute_name, doc.attribute_value],
+                )?;
+                upsert(transaction, doc)?;
+            }
+            Ok(())
+        })?)
+    }
+
+    fn remove_assignments(
+        &self,
+        predicate: &dyn Fn(&str) -> bool,
+    ) -> Result<usize, GrowthbookError> {
+        Ok(self.write(|transaction| {
+            let mut statement = transaction.prepare("SELECT DISTINCT assignment_key FROM growthbook_sticky_assignments")?;
+            let keys = statement.query_map([], |row| row.get::<_, String>(0))?.collect::<rusqlite::Result<Vec<_>>>()?;
+            let mut removed = 0;
+            for key in keys.iter().filter(|key| predicate(key)) {
+                removed += transaction.execute("DELETE FROM growthbook_sticky_assignments WHERE assignment_key = ?1", params![key])?;
+            }
+            Ok(removed)
+        })?)
+    }
+
+    fn variation_counts(
+        &self,
+        experiment_key: &str,
+        bucket_version: i64,
+        attribute_name: &str,
+    ) -> Result<BTreeMap<String, usize>, GrowthbookError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare("SELECT variation, COUNT(*) FROM growthbook_sticky_assignments WHERE assignment_key = ?1 AND attribute_name = ?2 GROUP BY variation")?;
+        let counts = statement.query_map(params![assignment_key(experiment_key, bucket_version), attribute_name], |row| {
+            Ok((row.get(0)?, row.get::<_, i64>(1)? as usize))
+        })?;
+        Ok(counts.collect::<rusqlite::Result<_>>()?)
+    }
+}
@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+/// How long the stream may stay silent before it is considered dead and
+/// reconnected.
+pub const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// A dispatched server-sent event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    pub event: String,
+    pub data: String,
+}
+
+/// Incremental `text/event-stream` parser: feed it chunks as they arrive and
+/// it returns the events they complete. Chunks may split lines (or UTF-8
+/// characters) anywhere.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    pending: Vec<u8>,
+    // How much of `pending` is known to hold no line break, so a long line
+    // arriving in many chunks is only scanned once.
+    scanned: usize,
+    event: String,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    pub fn push(
+        &mut self,
+        chunk: &[u8],
+    ) -> Vec<SseEvent> {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        let mut line_start = 0;
+        let mut search_from = self.scanned;
+        while let Some(offset) = pending[search_from..].iter().position(|byte| *byte == b'\n') {
+            let line_end = search_from + offset;
+            let line = String::from_utf8_lossy(&pending[line_start..line_end]);
+            if let Some(event) = self.process_line(line.trim_end_matches('\r')) {
+                events.push(event);
+            }
+            line_start = line_end + 1;
+            search_from = line_start;
+        }
+
+        // Completed lines are dropped in one go rather than one by one.
+        pending.drain(..line_start);
+        self.scanned = pending.len();
+        self.pending = pending;
+        events
+    }
+
+    fn process_line(
+        &mut self,
+        line: &str,
+    ) -> Option<SseEvent> {
+        // A blank line dispatches the event; events without data are dropped.
+        if line.is_empty() {
+            let event = std::mem::take(&mut self.event);
+            let data = std::mem::take(&mut self.data);
+            if data.is_empty() {
+                return None;
+            }
+            return Some(SseEvent {
+                event: if event.is_empty() { String::from("message") } else { event },
+                data: data.join("\n"),
+            });
+        }
+
+        // Lines starting with `:` are comments (often keep-alives).
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => self.data.push(value.to_string()),
+            _ => {},
+        }
+        None
+    }
+}
+
+/// Exponential reconnect delay: doubles from one second up to `max` (never
+/// below one second, so a zero refresh interval can't spin) and starts over
+/// once the stream delivers again.
+#[derive(Debug)]
+pub struct Backoff {
+    next: Duration,
+    max: Duration,
+}
+
+impl Backoff {
+    pub fn new(max: Duration) -> Self {
+        Self {
+            next: INITIAL_RECONNECT_DELAY,
+            max: max.max(INITIAL_RECONNECT_DELAY),
+        }
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(self.max);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.next = INITIAL_RECONNECT_DELAY;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::streaming::{Backoff, SseEvent, SseParser};
+
+    #[test]
+    fn parses_events_split_across_chunks() {
+        let mut parser = SseParser::default();
+
+        assert!(parser.push(b": keep-alive\r\nevent: feat").is_empty());
+        assert!(parser.push(b"ures\r\ndata: {\"a\":\ndata: 1}\r\n").is_empty());
+        let events = parser.push(b"\r\ndata: plain\n\nevent: empty\n\n");
+
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: String::from("features"),
+                    data: String::from("{\"a\":\n1}"),
+                },
+                SseEvent {
+                    event: String::from("message"),
+                    data: String::from("plain"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_a_line_split_across_many_chunks() {
+        let mut parser = SseParser::default();
+
+        for chunk in [&b"data: "[..], b"lo", b"ng", b" line\r", b"\n"] {
+            assert!(parser.push(chunk).is_empty());
+        }
+        let events = parser.push(b"\n");
+
+        assert_eq!(
+            events,
+            vec![SseEvent {
+                event: String::from("message"),
+                data: String::from("long line"),
+            }]
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max_and_resets() {
+        let mut backoff = Backoff::new(Duration::from_secs(5));
+
+        let delays: Vec<u64> = (0..5).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn backoff_never_drops_below_initial_delay() {
+        let mut backoff = Backoff::new(Duration::ZERO);
+
+        let delays: Vec<u64> = (0..3).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, vec![1, 1, 1]);
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use crate::model_public::ExperimentResult;
+
+/// Default number of exposures remembered for de-duplication.
+pub const DEFAULT_TRACKED_EXPERIMENTS_CAPACITY: usize = 1000;
+
+// (hash attribute, hash value, experiment key, variation id)
+pub(crate) type TrackingKey = (String, String, String, i64);
+
+/// Remembers which exposures were already sent to `on_experiment_viewed`,
+/// like the JS SDK's `_trackedExperiments`, but bounded: once full, the least
+/// recently seen exposure is forgotten.
+#[derive(Debug)]
+pub struct TrackedExperiments {
+    capacity: usize,
+    inner: Mutex<Lru>,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    last_seen: HashMap<TrackingKey, u64>,
+    by_age: BTreeMap<u64, TrackingKey>,
+    tick: u64,
+}
+
+impl TrackedExperiments {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Lru::default()),
+        }
+    }
+
+    /// Records the exposure and returns whether it is new, i.e. whether it
+    /// should be tracked.
+    pub fn should_track(
+        &self,
+        result: &ExperimentResult,
+    ) -> bool {
+        if self.capacity == 0 {
+            return true;
+        }
+
+        let key = tracking_key(result);
+        let Ok(mut lru) = self.inner.lock() else {
+            return true;
+        };
+        lru.tick += 1;
+        let tick = lru.tick;
+
+        if let Some(previous) = lru.last_seen.insert(key.clone(), tick) {
+            lru.by_age.remove(&previous);
+            lru.by_age.insert(tick, key);
+            return false;
+        }
+
+        lru.by_age.insert(tick, key);
+        if lru.by_age.len() > self.capacity {
+            if let Some((_, oldest)) = lru.by_age.pop_first() {
+                lru.last_seen.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// What makes two exposures the same for de-duplication.
+pub(crate) fn tracking_key(result: &ExperimentResult) -> TrackingKey {
+    (
+        result.hash_attribute.clone().unwrap_or_default(),
+        result.hash_value.as_ref().map(|value| value.to_string()).unwrap_or_default(),
+        result.experiment_key.clone(),
+        result.variation_id,
+    )
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use growthbook_rust::cache::BoxFuture;
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use growthbook_rust::sticky_bucket::{sticky_attribute_key, AsyncStickyBucketService, InMemoryStickyBucketService, StickyAssignmentsDocument};
+use serde_json::json;
+
+#[derive(Debug, Default)]
+struct CountingStickyBucketService {
+    documents: Mutex<HashMap<String, StickyAssignmentsDocument>>,
+    prefetches: AtomicUsize,
+    saves: Mutex<Vec<StickyAssignmentsDocument>>,
+}
+
+impl CountingStickyBucketService {
+    fn with_document(doc: StickyAssignmentsDocument) -> Self {
+        let service = Self::default();
+        service.documents.lock().unwrap().insert(doc.key(), doc);
+        service
+    }
+}
+
+impl AsyncStickyBucketService for CountingStickyBucketService {
+    fn get_all_assignments<'a>(
+        &'a self,
+        attributes: &'a HashMap<String, String>,
+    ) -> BoxFuture<'a, HashMap<String, StickyAssignmentsDocument>> {
+        Box::pin(async move {
+            self.prefetches.fetch_add(1, Ordering::SeqCst);
+            let documents = self.documents.lock().unwrap();
+            attributes
+                .iter()
+                .filter_map(|(name, value)| documents.get(&sticky_attribute_key(name, value)))
+                .map(|doc| (doc.key(), doc.clone()))
+                .collect()
+        })
+    }
+
+    fn save_assignments(
+        &self,
+        doc: StickyAssignmentsDocument,
+    ) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            self.saves.lock().unwrap().push(doc.clone());
+            self.documents.lock().unwrap().insert(doc.key(), doc);
+        })
+    }
+}
+
+fn attributes(id: &str) -> Option<Vec<GrowthBookAttribute>> {
+    Some(GrowthBookAttribute::from(json!({ "id": id })).expect("Failed to create attributes"))
+}
+
+fn document(
+    id: &str,
+    assignments: &[(&str, &str)],
+) -> StickyAssignmentsDocument {
+    StickyAssignmentsDocument::new("id", id, assignments.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect())
+}
+
+// Without a sticky bucket, id=1 hashes to variation 1 of "my-test".
+async fn client(service: Arc<dyn AsyncStickyBucketService>) -> GrowthBookClient {
+    GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "feature": {
+                "defaultValue": "default",
+                "rules": [{ "key": "my-test", "variations": ["control", "treatment"] }]
+            },
+            "other": {
+                "defaultValue": "default",
+                "rules": [{ "key": "other-test", "variations": ["a", "b"] }]
+            }
+        }))
+        .unwrap()
+        .async_sticky_bucket_service(service)
+        .build()
+        .await
+        .expect("Failed to build client")
+}
+
+#[tokio::test]
+async fn serves_prefetched_assignment() {
+    let service = Arc::new(CountingStickyBucketService::with_document(document("1", &[("my-test__0", "0")])));
+    let client = client(service.clone()).await;
+
+    let result = client.feature_result_async("feature", attributes("1")).await;
+
+    assert_eq!(result.value, json!("control"));
+    let experiment_result = result.experiment_result.expect("experiment result");
+    assert!(experiment_result.sticky_bucket_used);
+    assert_eq!(service.prefetches.load(Ordering::SeqCst), 1);
+    assert!(service.saves.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn prefetches_once_and_saves_changed_documents() {
+    let service = Arc::new(CountingStickyBucketService::default());
+    let client = client(service.clone()).await;
+
+    let results = client.evaluate_all_async(attributes("1")).await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(service.prefetches.load(Ordering::SeqCst), 1);
+    let saves = service.saves.lock().unwrap().clone();
+    assert_eq!(saves.len(), 1);
+    assert_eq!(saves[0].key(), "id||1");
+    assert_eq!(saves[0].assignments.len(), 2);
+    assert!(saves[0].assignments.contains_key("my-test__0"));
+    assert!(saves[0].assignments.contains_key("other-test__0"));
+
+    // Re-evaluating with the saved documents changes nothing.
+    client.evaluate_all_async(attributes("1")).await;
+    assert_eq!(service.prefetches.load(Ordering::SeqCst), 2);
+    assert_eq!(service.saves.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn synchronous_getters_skip_async_service() {
+    let service = Arc::new(CountingStickyBucketService::with_document(document("1", &[("my-test__0", "0")])));
+    let client = client(service.clone()).await;
+
+    let result = client.feature_result("feature", attributes("1"));
+
+    assert_eq!(result.value, json!("treatment"));
+    assert_eq!(service.prefetches.load(Ordering::SeqCst), 0);
+}
+
+#[cfg(feature = "tracing")]
+#[tokio::test]
+async fn synchronous_getters_log_skipping_async_service() {
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    #[derive(Clone, Default)]
+    struct Logs(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Logs {
+        fn write(
+            &mut self,
+            buf: &[u8],
+        ) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let client = client(Arc::new(InMemoryStickyBucketService::new())).await;
+    let logs = Logs::default();
+    let writer = logs.clone();
+    let guard = tracing_subscriber::fmt().with_writer(move || writer.clone()).with_ansi(false).finish().set_default();
+
+    client.feature_result("feature", attributes("1"));
+    drop(guard);
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    assert!(logs.contains("'feature_result' evaluates without sticky buckets"));
+}
+
+#[tokio::test]
+async fn in_memory_service_is_async() {
+    let service = Arc::new(InMemoryStickyBucketService::from_documents([document("1", &[("my-test__0", "0")])]));
+    let client = client(service).await;
+
+    let result = client.feature_result_async("feature", attributes("1")).await;
+
+    assert_eq!(result.value, json!("control"));
+}
+
+#[tokio::test]
+async fn rejects_sync_and_async_services_together() {
+    let result = GrowthBookClientBuilder::new()
+        .sticky_bucket_service(Arc::new(InMemoryStickyBucketService::new()))
+        .async_sticky_bucket_service(Arc::new(InMemoryStickyBucketService::new()))
+        .build()
+        .await;
+
+    assert!(result.is_err());
+}
@@ -1,3 +1,6 @@
+// Shared by every test crate; each uses only some of it.
+#![allow(dead_code)]
+
 use std::net::{SocketAddr, TcpListener};
 
 use growthbook_rust::client::GrowthBookClient;
@@ -9,7 +12,6 @@ use uuid::Uuid;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
-#[allow(dead_code)]
 pub struct TestContext {
     pub mock_server: MockServer,
     pub growthbook: GrowthBookClient,
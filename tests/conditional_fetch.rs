@@ -0,0 +1,88 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use serde_json::json;
+use uuid::Uuid;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mock_payload(
+    mock_server: &MockServer,
+    sdk_key: Uuid,
+) {
+    Mock::given(method("GET"))
+        .and(path(format!("/api/features/{sdk_key}")))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v1\"")
+                .insert_header("Last-Modified", "Wed, 21 Oct 2026 07:28:00 GMT")
+                .set_body_json(json!({ "features": { "dark-mode": { "defaultValue": true } } })),
+        )
+        .mount(mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path(format!("/api/features/{sdk_key}")))
+        .and(header("If-None-Match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .with_priority(1)
+        .mount(mock_server)
+        .await;
+}
+
+async fn client(
+    mock_server: &MockServer,
+    sdk_key: Uuid,
+    ttl: Duration,
+    refreshes: Arc<AtomicUsize>,
+) -> GrowthBookClient {
+    GrowthBookClientBuilder::new()
+        .api_url(mock_server.uri())
+        .client_key(sdk_key.to_string())
+        .ttl(ttl)
+        .add_on_refresh(Box::new(move || {
+            refreshes.fetch_add(1, Ordering::SeqCst);
+        }))
+        .build()
+        .await
+        .expect("Failed to build client")
+}
+
+#[tokio::test]
+async fn not_modified_keeps_features_without_on_refresh() {
+    let mock_server = MockServer::start().await;
+    let sdk_key = Uuid::now_v7();
+    mock_payload(&mock_server, sdk_key).await;
+    let refreshes = Arc::new(AtomicUsize::new(0));
+
+    let client = client(&mock_server, sdk_key, Duration::from_secs(0), refreshes.clone()).await;
+    client.refresh().await;
+    client.refresh().await;
+
+    assert!(client.is_on("dark-mode", None));
+    assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+    let requests = mock_server.received_requests().await.expect("Failed to read requests");
+    assert_eq!(requests.len(), 3);
+    assert!(requests[1..]
+        .iter()
+        .all(|request| request.headers.get("If-Modified-Since").is_some_and(|value| value == "Wed, 21 Oct 2026 07:28:00 GMT")));
+}
+
+#[tokio::test]
+async fn not_modified_extends_cache_ttl() {
+    let mock_server = MockServer::start().await;
+    let sdk_key = Uuid::now_v7();
+    mock_payload(&mock_server, sdk_key).await;
+    let refreshes = Arc::new(AtomicUsize::new(0));
+
+    let client = client(&mock_server, sdk_key, Duration::from_millis(200), refreshes.clone()).await;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    client.refresh().await;
+    // The 304 re-cached the payload, so this one is served from the cache.
+    client.refresh().await;
+
+    assert!(client.is_on("dark-mode", None));
+    assert_eq!(mock_server.received_requests().await.expect("Failed to read requests").len(), 2);
+}
@@ -0,0 +1,102 @@
+use std::sync::{Arc, Mutex};
+
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use serde_json::json;
+
+fn attributes(id: &str) -> Option<Vec<GrowthBookAttribute>> {
+    Some(GrowthBookAttribute::from(json!({ "id": id })).expect("Failed to create attributes"))
+}
+
+// id=1 is in the measured population (the passthrough variation 1) of the
+// holdout both "checkout" and "search" share.
+async fn client() -> (GrowthBookClient, Arc<Mutex<Vec<String>>>) {
+    let viewed = Arc::new(Mutex::new(Vec::new()));
+    let viewed_in_callback = viewed.clone();
+    let client = GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "$holdout:hld_1": {
+                "defaultValue": "genpop",
+                "rules": [{
+                    "key": "holdout-hld_1",
+                    "hashVersion": 2,
+                    "variations": ["holdoutcontrol", "holdouttreatment"],
+                    "weights": [0.5, 0.5],
+                    "meta": [{}, { "passthrough": true }]
+                }]
+            },
+            "checkout": {
+                "defaultValue": "old",
+                "holdout": { "id": "$holdout:hld_1" },
+                "rules": [{ "key": "checkout-exp", "variations": ["control", "treatment"] }]
+            },
+            "search": {
+                "defaultValue": "old",
+                "holdout": { "id": "$holdout:hld_1" },
+                "rules": [{ "key": "search-exp", "variations": ["control", "treatment"] }]
+            },
+            "beta:dark-mode": {
+                "defaultValue": false,
+                "rules": [{ "parentConditions": [{ "id": "checkout", "condition": { "value": "control" } }], "force": true }]
+            },
+            "beta:new-nav": { "defaultValue": true },
+            "cycle-a": {
+                "defaultValue": 1,
+                "rules": [{ "parentConditions": [{ "id": "cycle-b", "condition": { "value": 2 } }], "force": 3 }]
+            },
+            "cycle-b": {
+                "defaultValue": 2,
+                "rules": [{ "parentConditions": [{ "id": "cycle-a", "condition": { "value": 1 } }], "force": 4 }]
+            }
+        }))
+        .unwrap()
+        .forced_features([(String::from("beta:forced"), json!("pinned"))].into_iter().collect())
+        .deduplicate_experiment_viewed(false)
+        .on_experiment_viewed(Box::new(move |result| {
+            viewed_in_callback.lock().unwrap().push(result.experiment_key);
+        }))
+        .build()
+        .await
+        .expect("Failed to build client");
+    (client, viewed)
+}
+
+#[tokio::test]
+async fn evaluate_all_matches_single_feature_evaluation() {
+    let (client, _) = client().await;
+
+    let results = client.evaluate_all(attributes("1"));
+
+    assert_eq!(results.len(), 8);
+    for (feature_name, result) in &results {
+        let single = client.feature_result(feature_name, attributes("1"));
+        assert_eq!(result.value, single.value, "{feature_name}");
+        assert_eq!(result.source, single.source, "{feature_name}");
+    }
+    assert_eq!(results["beta:dark-mode"].value, json!(true));
+    assert_eq!(results["beta:forced"].source, "override");
+    assert_eq!(results["cycle-a"].source, "cyclicPrerequisite");
+    assert_eq!(results["cycle-b"].source, "cyclicPrerequisite");
+}
+
+#[tokio::test]
+async fn evaluate_all_tracks_each_exposure_once() {
+    let (client, viewed) = client().await;
+
+    client.evaluate_all(attributes("1"));
+
+    let mut viewed = viewed.lock().unwrap().clone();
+    viewed.sort();
+    assert_eq!(viewed, vec!["checkout-exp", "search-exp"]);
+}
+
+#[tokio::test]
+async fn evaluate_all_with_prefix_filters_feature_keys() {
+    let (client, _) = client().await;
+
+    let results = client.evaluate_all_with_prefix("beta:", attributes("1"));
+
+    let mut keys: Vec<&String> = results.keys().collect();
+    keys.sort();
+    assert_eq!(keys, vec!["beta:dark-mode", "beta:forced", "beta:new-nav"]);
+}
@@ -0,0 +1,62 @@
+use std::sync::{Arc, Mutex};
+
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use serde_json::json;
+
+fn attributes(id: &str) -> Option<Vec<GrowthBookAttribute>> {
+    Some(GrowthBookAttribute::from(json!({ "id": id })).expect("Failed to create attributes"))
+}
+
+async fn client(builder: GrowthBookClientBuilder) -> (GrowthBookClient, Arc<Mutex<Vec<String>>>) {
+    let viewed = Arc::new(Mutex::new(Vec::new()));
+    let viewed_in_callback = viewed.clone();
+    let client = builder
+        .features_json(json!({
+            "checkout": {
+                "defaultValue": "default",
+                "rules": [{ "key": "my-test", "variations": ["control", "treatment"] }]
+            }
+        }))
+        .unwrap()
+        .on_experiment_viewed(Box::new(move |result| {
+            viewed_in_callback.lock().unwrap().push(result.hash_value.map(|it| it.to_string()).unwrap_or_default());
+        }))
+        .build()
+        .await
+        .expect("Failed to build client");
+    (client, viewed)
+}
+
+#[tokio::test]
+async fn experiment_viewed_fires_once_per_user_and_variation() {
+    let (client, viewed) = client(GrowthBookClientBuilder::new()).await;
+
+    for _ in 0..3 {
+        client.feature_result("checkout", attributes("1"));
+        client.feature_result("checkout", attributes("2"));
+    }
+
+    assert_eq!(*viewed.lock().unwrap(), vec![r#""1""#, r#""2""#]);
+}
+
+#[tokio::test]
+async fn experiment_viewed_forgets_least_recently_seen_exposures() {
+    let (client, viewed) = client(GrowthBookClientBuilder::new().tracked_experiments_capacity(1)).await;
+
+    client.feature_result("checkout", attributes("1"));
+    client.feature_result("checkout", attributes("2"));
+    client.feature_result("checkout", attributes("1"));
+
+    assert_eq!(viewed.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn experiment_viewed_deduplication_can_be_disabled() {
+    let (client, viewed) = client(GrowthBookClientBuilder::new().deduplicate_experiment_viewed(false)).await;
+
+    client.feature_result("checkout", attributes("1"));
+    client.feature_result("checkout", attributes("1"));
+
+    assert_eq!(viewed.lock().unwrap().len(), 2);
+}
@@ -0,0 +1,91 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use growthbook_rust::client::{GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::{GrowthBookAttribute, RuleOutcome, RuleTraceKind};
+use growthbook_rust::sticky_bucket::{InMemoryStickyBucketService, StickyBucketAdmin};
+use serde_json::json;
+
+#[tokio::test]
+async fn explain_feature_traces_every_rule_visited() {
+    let viewed = Arc::new(AtomicUsize::new(0));
+    let viewed_in_callback = viewed.clone();
+
+    let client = GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "parent-flag": { "defaultValue": false },
+            "checkout": {
+                "defaultValue": "default",
+                "rules": [
+                    { "id": "fr_us", "condition": { "country": "US" }, "force": "us" },
+                    { "parentConditions": [{ "id": "parent-flag", "condition": { "value": true } }], "force": "parent" },
+                    { "filters": [{ "seed": "ns", "ranges": [] }], "force": "filtered" },
+                    { "force": "rollout", "coverage": 0.0 },
+                    { "key": "my-test", "variations": ["control", "treatment"] }
+                ]
+            }
+        }))
+        .unwrap()
+        .on_experiment_viewed(Box::new(move |_| {
+            viewed_in_callback.fetch_add(1, Ordering::SeqCst);
+        }))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let attributes = GrowthBookAttribute::from(json!({ "id": "1", "country": "CA" })).expect("Failed to create attributes");
+    let explanation = client.explain_feature("checkout", Some(attributes));
+
+    assert_eq!(explanation.result.value, json!("treatment"));
+    let outcomes: Vec<RuleOutcome> = explanation.trace.iter().map(|rule| rule.outcome).collect();
+    assert_eq!(
+        outcomes,
+        vec![
+            RuleOutcome::ConditionFailed,
+            RuleOutcome::ParentSkipped,
+            RuleOutcome::FilteredOut,
+            RuleOutcome::NotInCoverage,
+            RuleOutcome::Matched
+        ]
+    );
+    assert_eq!(explanation.trace[0].rule_id.as_deref(), Some("fr_us"));
+    assert_eq!(explanation.trace[3].kind, RuleTraceKind::Rollout);
+    assert_eq!(explanation.trace[3].hash_attribute.as_deref(), Some("id"));
+    assert!(explanation.trace[3].bucket.is_some());
+
+    let matched = &explanation.trace[4];
+    assert_eq!(matched.kind, RuleTraceKind::Experiment);
+    assert_eq!(matched.rule_index, 4);
+    assert_eq!(matched.hash_value, Some(json!("1")));
+    assert_eq!(matched.bucket, explanation.result.experiment_result.and_then(|it| it.bucket));
+
+    // Explaining is side-effect free for tracking.
+    assert_eq!(viewed.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn explain_feature_saves_no_sticky_buckets() {
+    let sticky_buckets = Arc::new(InMemoryStickyBucketService::new());
+    let client = GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "checkout": {
+                "defaultValue": "default",
+                "rules": [{ "key": "my-test", "variations": ["control", "treatment"] }]
+            }
+        }))
+        .unwrap()
+        .sticky_bucket_service(sticky_buckets.clone())
+        .build()
+        .await
+        .expect("Failed to build client");
+    let attributes = GrowthBookAttribute::from(json!({ "id": "1" })).expect("Failed to create attributes");
+
+    let explanation = client.explain_feature("checkout", Some(attributes.clone()));
+
+    assert_eq!(explanation.result.value, json!("treatment"));
+    assert!(sticky_buckets.all_documents().unwrap().is_empty());
+
+    // Evaluating for real still enrolls the user.
+    client.feature_result("checkout", Some(attributes));
+    assert_eq!(sticky_buckets.all_documents().unwrap().len(), 1);
+}
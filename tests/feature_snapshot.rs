@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Barrier};
+
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use serde_json::json;
+
+async fn client() -> GrowthBookClient {
+    GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "flag": { "defaultValue": true },
+            "color": { "defaultValue": "blue" }
+        }))
+        .unwrap()
+        .build()
+        .await
+        .expect("Failed to build client")
+}
+
+#[tokio::test]
+async fn evaluations_share_one_snapshot_until_it_changes() {
+    let client = client().await;
+
+    let before = client.snapshot();
+    assert!(client.is_on("flag", None));
+    assert!(Arc::ptr_eq(&before, &client.snapshot()));
+
+    client.set_forced_features(HashMap::from([(String::from("color"), json!("red"))]));
+
+    let after = client.snapshot();
+    assert!(!Arc::ptr_eq(&before, &after));
+    assert_eq!(client.feature_result("color", None).value, json!("red"));
+    // A pinned snapshot keeps evaluating the features it was taken with.
+    assert_eq!(before.check("color", &None).value, json!("blue"));
+    assert_eq!(after.check("color", &None).value, json!("red"));
+}
+
+#[tokio::test]
+async fn snapshots_apply_the_forced_variations_they_were_taken_with() {
+    let client = GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "checkout": {
+                "defaultValue": "a",
+                "rules": [{ "key": "checkout-test", "variations": ["a", "b"], "coverage": 0.0 }]
+            }
+        }))
+        .unwrap()
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let user = Some(GrowthBookAttribute::from(json!({ "id": "1" })).expect("Failed to create attributes"));
+    let before = client.snapshot();
+    client.force_variation("checkout-test", 1);
+
+    assert_eq!(client.snapshot().check("checkout", &user).value, json!("b"));
+    assert_eq!(before.check("checkout", &user).value, json!("a"));
+}
+
+#[tokio::test]
+async fn readers_run_while_the_snapshot_is_replaced() {
+    let client = client().await;
+    let done = AtomicBool::new(false);
+    let started = Barrier::new(5);
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                started.wait();
+                while !done.load(Ordering::SeqCst) {
+                    let value = client.feature_result("color", None).value;
+                    assert!(value == json!("blue") || value == json!("red"), "{value}");
+                }
+            });
+        }
+
+        started.wait();
+        for i in 0..200 {
+            let color = if i % 2 == 0 { "red" } else { "blue" };
+            client.set_forced_features(HashMap::from([(String::from("color"), json!(color))]));
+        }
+        done.store(true, Ordering::SeqCst);
+    });
+
+    assert_eq!(client.feature_result("color", None).value, json!("blue"));
+}
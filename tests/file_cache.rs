@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use growthbook_rust::cache::{FeatureCache, FileCache};
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::dto::GrowthBookResponse;
+use serde_json::json;
+use uuid::Uuid;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn cache_directory() -> PathBuf {
+    std::env::temp_dir().join(format!("growthbook-file-cache-{}", Uuid::now_v7()))
+}
+
+fn payload() -> GrowthBookResponse {
+    serde_json::from_value(json!({
+        "features": {
+            "checkout": {
+                "defaultValue": "old",
+                "rules": [{ "condition": { "country": "BR" }, "force": "new" }]
+            }
+        }
+    }))
+    .expect("Failed to create payload")
+}
+
+#[tokio::test]
+async fn entries_persist_until_ttl_and_stay_available_as_stale() {
+    let directory = cache_directory();
+    let cache = FileCache::new(&directory, Duration::from_millis(100));
+
+    cache.set("features", payload()).await;
+
+    // A new instance reads what the previous one wrote.
+    let restarted = FileCache::new(&directory, Duration::from_millis(100));
+    let cached = restarted.get("features").await.expect("Missing cached payload");
+    assert!(cached.features.is_some_and(|features| features.contains_key("checkout")));
+    assert!(restarted.get("other").await.is_none());
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(restarted.get("features").await.is_none());
+    assert!(restarted.get_stale("features").await.is_some());
+
+    // Writes are renamed into place; no temporary files are left behind.
+    let files: Vec<_> = std::fs::read_dir(&directory).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+    assert_eq!(files.len(), 1);
+    assert!(files[0].to_string_lossy().ends_with(".json"));
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[tokio::test]
+async fn entries_hide_their_keys_and_are_pruned() {
+    let directory = cache_directory();
+    let cache = FileCache::new(&directory, Duration::from_millis(100));
+    let files = || std::fs::read_dir(&directory).unwrap().count();
+
+    cache.set("remote-eval?email=jane@example.com", payload()).await;
+    cache.set("features", payload()).await;
+    let written = std::fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect::<String>();
+    assert!(!written.contains("jane@example.com"));
+    assert_eq!(files(), 2);
+
+    cache.remove("remote-eval?email=jane@example.com").await;
+    assert!(cache.get_stale("remote-eval?email=jane@example.com").await.is_none());
+    assert_eq!(files(), 1);
+
+    // Writing doesn't prune: expired entries stay as the stale fallback.
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    cache.set("other", payload()).await;
+    assert!(cache.get_stale("features").await.is_some());
+    assert_eq!(files(), 2);
+
+    // Pruning spares the entries this cache used, but not another process's.
+    FileCache::new(&directory, Duration::from_millis(100)).set("abandoned", payload()).await;
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(cache.prune(Duration::from_millis(100)).await, 1);
+    assert!(cache.get_stale("features").await.is_some());
+    assert!(cache.get_stale("other").await.is_some());
+    assert!(cache.get_stale("abandoned").await.is_none());
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+async fn client(
+    mock_server: &MockServer,
+    sdk_key: Uuid,
+    directory: &PathBuf,
+) -> GrowthBookClient {
+    GrowthBookClientBuilder::new()
+        .api_url(mock_server.uri())
+        .client_key(sdk_key.to_string())
+        .cache(Arc::new(FileCache::new(directory, Duration::from_secs(0))))
+        .build()
+        .await
+        .expect("Failed to build client")
+}
+
+#[tokio::test]
+async fn cached_payload_is_used_when_initial_fetch_fails() {
+    let directory = cache_directory();
+    let mock_server = MockServer::start().await;
+    let sdk_key = Uuid::now_v7();
+    Mock::given(method("GET"))
+        .and(path(format!("/api/features/{sdk_key}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "features": { "dark-mode": { "defaultValue": true } } })))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/api/features/{sdk_key}")))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+
+    let first = client(&mock_server, sdk_key, &directory).await;
+    assert!(first.is_on("dark-mode", None));
+    assert!(!first.is_stale());
+
+    // A restarted process can't reach the API but starts from the file.
+    let restarted = client(&mock_server, sdk_key, &directory).await;
+    assert!(restarted.is_on("dark-mode", None));
+    assert!(restarted.is_stale());
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
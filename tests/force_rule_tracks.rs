@@ -0,0 +1,76 @@
+use std::sync::{Arc, Mutex};
+
+use growthbook_rust::client::{GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use serde_json::json;
+
+#[tokio::test]
+async fn force_rule_tracks_fire_experiment_viewed() {
+    let viewed = Arc::new(Mutex::new(Vec::new()));
+    let viewed_in_callback = viewed.clone();
+
+    let client = GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "checkout": {
+                "defaultValue": "old",
+                "rules": [{
+                    "force": "new",
+                    "tracks": [{
+                        "experiment": { "key": "safe-rollout", "variations": ["old", "new"] },
+                        "result": {
+                            "featureId": "checkout",
+                            "value": "new",
+                            "variationId": 1,
+                            "key": "1",
+                            "inExperiment": true,
+                            "hashUsed": true,
+                            "hashAttribute": "id",
+                            "hashValue": "1",
+                            "bucket": 0.42
+                        }
+                    }]
+                }]
+            }
+        }))
+        .unwrap()
+        .on_experiment_viewed(Box::new(move |result| {
+            viewed_in_callback.lock().unwrap().push((result.experiment_key, result.variation_id));
+        }))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let attributes = GrowthBookAttribute::from(json!({ "id": "1" })).expect("Failed to create attributes");
+    let result = client.feature_result("checkout", Some(attributes));
+
+    assert_eq!(result.value, json!("new"));
+    assert_eq!(result.source, "force");
+    assert_eq!(result.tracks.len(), 1);
+    assert_eq!(*viewed.lock().unwrap(), vec![(String::from("safe-rollout"), 1)]);
+}
+
+#[tokio::test]
+async fn force_rule_tracks_accept_null_feature_id() {
+    let client = GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "checkout": {
+                "defaultValue": "old",
+                "rules": [{
+                    "force": "new",
+                    "tracks": [{
+                        "experiment": { "key": "safe-rollout", "variations": ["old", "new"] },
+                        "result": { "featureId": null, "value": "new", "variationId": 1, "key": "1", "inExperiment": true, "hashUsed": true }
+                    }]
+                }]
+            }
+        }))
+        .expect("A null featureId should not reject the payload")
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let result = client.feature_result("checkout", None);
+
+    assert_eq!(result.value, json!("new"));
+    assert_eq!(result.tracks[0].result.feature_id, "");
+}
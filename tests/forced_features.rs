@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use growthbook_rust::client::{GrowthBookClientBuilder, GrowthBookClientTrait};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn forced_features_bypass_rules() {
+    let client = GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "checkout": { "defaultValue": "old", "rules": [{ "force": "rule" }] }
+        }))
+        .unwrap()
+        .forced_features(HashMap::from([(String::from("checkout"), json!("pinned")), (String::from("not-in-payload"), json!(true))]))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let result = client.feature_result("checkout", None);
+    assert_eq!(result.value, json!("pinned"));
+    assert_eq!(result.source, "override");
+
+    // Features missing from the payload can be forced too.
+    assert!(client.is_on("not-in-payload", None));
+
+    client.set_forced_features(HashMap::new());
+    let result = client.feature_result("checkout", None);
+    assert_eq!(result.value, json!("rule"));
+    assert_eq!(result.source, "force");
+}
+
+#[tokio::test]
+async fn forced_features_survive_refresh() {
+    let mock_server = MockServer::start().await;
+    let sdk_key = "forced_features_key";
+    Mock::given(method("GET"))
+        .and(path(format!("/api/features/{}", sdk_key)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "features": { "kill-switch": { "defaultValue": false } }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = GrowthBookClientBuilder::new()
+        .api_url(mock_server.uri())
+        .client_key(sdk_key.to_string())
+        .ttl(Duration::from_secs(0))
+        .build()
+        .await
+        .expect("Failed to build client");
+    assert!(client.is_off("kill-switch", None));
+
+    client.set_forced_features(HashMap::from([(String::from("kill-switch"), json!(true))]));
+    client.refresh().await;
+
+    let result = client.feature_result("kill-switch", None);
+    assert!(result.on);
+    assert_eq!(result.source, "override");
+}
+
+#[tokio::test]
+async fn forced_features_apply_to_prerequisites() {
+    let client = GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "parent": { "defaultValue": false },
+            "child": {
+                "defaultValue": false,
+                "rules": [{ "parentConditions": [{ "id": "parent", "condition": { "value": true }, "gate": true }], "force": true }]
+            },
+            "orphan": {
+                "defaultValue": false,
+                "rules": [{ "parentConditions": [{ "id": "forced-only", "condition": { "value": "on" }, "gate": true }], "force": true }]
+            }
+        }))
+        .unwrap()
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    assert_eq!(client.feature_result("child", None).source, "prerequisite");
+    assert_eq!(client.feature_result("orphan", None).source, "prerequisite");
+
+    client.set_forced_features(HashMap::from([(String::from("parent"), json!(true)), (String::from("forced-only"), json!("on"))]));
+
+    assert!(client.is_on("child", None));
+    // A parent that only exists as a forced feature is not unknown.
+    assert!(client.is_on("orphan", None));
+}
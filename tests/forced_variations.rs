@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use growthbook_rust::client::{GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn attributes() -> Option<Vec<GrowthBookAttribute>> {
+    Some(GrowthBookAttribute::from(json!({ "id": "1" })).expect("Failed to create attributes"))
+}
+
+// id=1 hashes into variation 1 of both experiments on its own.
+async fn mock_server(sdk_key: &str) -> MockServer {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(format!("/api/features/{}", sdk_key)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "forcedVariations": { "my-test": 1, "other-test": 1 },
+            "features": {
+                "checkout": {
+                    "defaultValue": "default",
+                    "rules": [{ "key": "my-test", "variations": ["control", "treatment"] }]
+                },
+                "banner": {
+                    "defaultValue": "default",
+                    "rules": [{ "key": "other-test", "variations": ["control", "treatment"] }]
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+    mock_server
+}
+
+#[tokio::test]
+async fn local_forced_variations_override_payload_across_refresh() {
+    let sdk_key = "forced_variations_key";
+    let mock_server = mock_server(sdk_key).await;
+
+    let client = GrowthBookClientBuilder::new()
+        .api_url(mock_server.uri())
+        .client_key(sdk_key.to_string())
+        .ttl(Duration::from_secs(0))
+        .forced_variations(HashMap::from([(String::from("my-test"), 0)]))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    assert_eq!(client.feature_result("checkout", attributes()).value, json!("control"));
+    // Experiments without a local override still use the payload's.
+    assert_eq!(client.feature_result("banner", attributes()).value, json!("treatment"));
+
+    client.force_variation("other-test", 0);
+    client.refresh().await;
+
+    assert_eq!(client.feature_result("checkout", attributes()).value, json!("control"));
+    assert_eq!(client.feature_result("banner", attributes()).value, json!("control"));
+
+    client.set_forced_variations(HashMap::new());
+    assert_eq!(client.feature_result("checkout", attributes()).value, json!("treatment"));
+}
@@ -0,0 +1,135 @@
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use serde_json::{json, Value};
+
+fn attributes(id: &str) -> Option<Vec<GrowthBookAttribute>> {
+    Some(GrowthBookAttribute::from(json!({ "id": id })).expect("Failed to create attributes"))
+}
+
+// The holdout experiment's second variation is the measured population,
+// marked `passthrough` as in the conformance suite's holdout group. id=3
+// hashes to 0.367 on the holdout (held out); id=1 to 0.7086 (measured), then
+// into variation 0 of "checkout-exp".
+async fn client(meta: serde_json::Value) -> (GrowthBookClient, Arc<Mutex<Vec<(String, i64)>>>) {
+    let viewed = Arc::new(Mutex::new(Vec::new()));
+    let viewed_in_callback = viewed.clone();
+    let client = GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "$holdout:hld_1": {
+                "defaultValue": "genpop",
+                "rules": [{
+                    "key": "holdout-hld_1",
+                    "hashVersion": 2,
+                    "variations": ["holdoutcontrol", "holdouttreatment"],
+                    "weights": [0.5, 0.5],
+                    "meta": meta
+                }]
+            },
+            "checkout": {
+                "defaultValue": "old",
+                "holdout": { "id": "$holdout:hld_1" },
+                "rules": [{ "key": "checkout-exp", "variations": ["control", "treatment"] }]
+            }
+        }))
+        .unwrap()
+        .on_experiment_viewed(Box::new(move |result| {
+            viewed_in_callback.lock().unwrap().push((result.experiment_key, result.variation_id));
+        }))
+        .build()
+        .await
+        .expect("Failed to build client");
+    (client, viewed)
+}
+
+#[tokio::test]
+async fn held_out_users_get_default_value_and_only_holdout_exposure() {
+    let (client, viewed) = client(json!([{}, { "passthrough": true }])).await;
+
+    let result = client.feature_result("checkout", attributes("3"));
+
+    assert_eq!(result.value, json!("old"));
+    assert_eq!(result.source, "holdout");
+    assert_eq!(result.experiment.map(|it| it.key), Some(String::from("holdout-hld_1")));
+    assert_eq!(*viewed.lock().unwrap(), vec![(String::from("holdout-hld_1"), 0)]);
+}
+
+#[tokio::test]
+async fn measured_users_get_feature_rules() {
+    let (client, viewed) = client(json!([{}, { "passthrough": true }])).await;
+
+    let result = client.feature_result("checkout", attributes("1"));
+
+    assert_eq!(result.value, json!("control"));
+    assert_eq!(result.source, "experiment");
+    assert_eq!(*viewed.lock().unwrap(), vec![(String::from("checkout-exp"), 0)]);
+}
+
+#[tokio::test]
+async fn membership_follows_the_passthrough_meta() {
+    // Variation 0 is the measured population here, so id=1 is held out.
+    let (client, _) = client(json!([{ "passthrough": true }, {}])).await;
+
+    assert_eq!(client.feature_result("checkout", attributes("1")).source, "holdout");
+    assert_eq!(client.feature_result("checkout", attributes("3")).source, "experiment");
+}
+
+// The conformance suite's "Support holdout groups" case, loaded as-is: the
+// holdout is the feature's first rule.
+fn holdout_groups_case() -> (Value, Value) {
+    let content = fs::read_to_string("tests/all_cases.json").expect("Failed to read all_cases.json");
+    let all_cases: Value = serde_json::from_str(&content).expect("Failed to parse all_cases.json");
+    let case = all_cases["feature"]
+        .as_array()
+        .expect("feature cases should be an array")
+        .iter()
+        .find(|case| case[0] == "Support holdout groups")
+        .expect("Support holdout groups case should exist");
+    (case[1]["features"].clone(), case[3].clone())
+}
+
+#[tokio::test]
+async fn holdout_group_rule_serves_the_held_out_variation() {
+    let (features, expected) = holdout_groups_case();
+    let viewed = Arc::new(Mutex::new(Vec::new()));
+    let viewed_in_callback = viewed.clone();
+    let client = GrowthBookClientBuilder::new()
+        .features_json(features)
+        .unwrap()
+        .on_experiment_viewed(Box::new(move |result| {
+            viewed_in_callback.lock().unwrap().push((result.experiment_key, result.variation_id));
+        }))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let result = client.feature_result("feature", attributes("1"));
+
+    assert_eq!(result.value, expected["value"]);
+    assert_eq!(result.source, expected["source"]);
+    assert_eq!(
+        Some(result.experiment_result.expect("experiment result").variation_id),
+        expected["experimentResult"]["variationId"].as_i64()
+    );
+    assert_eq!(*viewed.lock().unwrap(), vec![(String::from("holdout"), 0)]);
+}
+
+// The same holdout group, served as its own feature and referenced through
+// `holdout`: id=1 is held out, so the feature's rules never run.
+#[tokio::test]
+async fn corpus_holdout_group_referenced_by_a_feature() {
+    let (mut features, _) = holdout_groups_case();
+    let holdout = features["feature"]["rules"][0].clone();
+    features["$holdout:hld_1"] = json!({ "defaultValue": 0, "rules": [holdout] });
+    features["feature"]["rules"].as_array_mut().unwrap().remove(0);
+    features["feature"]["holdout"] = json!({ "id": "$holdout:hld_1" });
+    let client = GrowthBookClientBuilder::new().features_json(features).unwrap().build().await.expect("Failed to build client");
+
+    let result = client.feature_result("feature", attributes("1"));
+
+    assert_eq!(result.value, json!(0));
+    assert_eq!(result.source, "holdout");
+    assert_eq!(result.experiment.map(|it| it.key), Some(String::from("holdout")));
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use growthbook_rust::client::{GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use serde_json::json;
+
+// id=1 hashes into variation 1 ("treatment") of "my-test".
+fn features() -> serde_json::Value {
+    json!({
+        "checkout": {
+            "defaultValue": "default",
+            "rules": [{ "key": "my-test", "variations": ["control", "treatment"] }]
+        }
+    })
+}
+
+fn attributes() -> Option<Vec<GrowthBookAttribute>> {
+    Some(GrowthBookAttribute::from(json!({ "id": "1" })).expect("Failed to create attributes"))
+}
+
+#[tokio::test]
+async fn disabled_client_skips_experiments_without_tracking() {
+    let viewed = Arc::new(AtomicUsize::new(0));
+    let viewed_in_callback = viewed.clone();
+
+    let client = GrowthBookClientBuilder::new()
+        .features_json(features())
+        .unwrap()
+        .enabled(false)
+        .on_experiment_viewed(Box::new(move |_| {
+            viewed_in_callback.fetch_add(1, Ordering::SeqCst);
+        }))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let result = client.feature_result("checkout", attributes());
+    assert_eq!(result.value, json!("default"));
+    assert_eq!(result.source, "defaultValue");
+    assert!(result.experiment_result.is_none());
+    assert_eq!(viewed.load(Ordering::SeqCst), 0);
+
+    client.set_enabled(true);
+    let result = client.feature_result("checkout", attributes());
+    assert_eq!(result.value, json!("treatment"));
+    assert_eq!(viewed.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn qa_mode_only_serves_forced_variations() {
+    let client = GrowthBookClientBuilder::new()
+        .features_json(features())
+        .unwrap()
+        .qa_mode(true)
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    assert_eq!(client.feature_result("checkout", attributes()).source, "defaultValue");
+
+    client.set_forced_variations(HashMap::from([(String::from("my-test"), 0)]));
+    let result = client.feature_result("checkout", attributes());
+    assert_eq!(result.value, json!("control"));
+    assert_eq!(result.source, "experiment");
+
+    client.set_qa_mode(false);
+    client.set_forced_variations(HashMap::new());
+    assert_eq!(client.feature_result("checkout", attributes()).value, json!("treatment"));
+}
@@ -67,6 +67,7 @@ async fn test_hybrid_mode_manual_features_overwrite() {
         GrowthBookFeature {
             default_value: Some(Value::String("present".to_string())),
             rules: None,
+            holdout: None,
         },
     );
 
@@ -0,0 +1,144 @@
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::dto::{option_map_to_attributes, Condition, GrowthBookFeature, GrowthBookFeatureRuleKind};
+use growthbook_rust::model_public::{Experiment, GrowthBookAttribute};
+use serde_json::json;
+
+fn attributes(value: serde_json::Value) -> Option<Vec<GrowthBookAttribute>> {
+    Some(GrowthBookAttribute::from(value).expect("Failed to create attributes"))
+}
+
+async fn client(features: serde_json::Value) -> GrowthBookClient {
+    GrowthBookClientBuilder::new().features_json(features).unwrap().build().await.expect("Failed to build client")
+}
+
+#[tokio::test]
+async fn reports_invalid_conditions_at_load() {
+    let client = client(json!({
+        "valid": {
+            "defaultValue": false,
+            "rules": [{ "condition": { "country": { "$in": ["BR", "US"] } }, "force": true }]
+        },
+        "invalid": {
+            "defaultValue": false,
+            "rules": [
+                { "condition": { "country": { "$regex": "(" } }, "force": true },
+                { "condition": { "plan": { "$in": "pro" }, "age": { "$between": [1, 2] } }, "force": true },
+                { "parentConditions": [{ "id": "valid", "condition": { "value": { "$exists": "yes" } } }], "force": true }
+            ]
+        }
+    }))
+    .await;
+
+    let errors = client.snapshot().condition_errors();
+    let paths: Vec<_> = errors.iter().map(|error| error.path.as_str()).collect();
+
+    assert_eq!(
+        paths,
+        vec![
+            "features.invalid.rules[0].condition.country.$regex",
+            "features.invalid.rules[1].condition.age.$between",
+            "features.invalid.rules[1].condition.plan.$in",
+            "features.invalid.rules[2].parentConditions[0].condition.value.$exists",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn invalid_parts_never_match() {
+    let client = client(json!({
+        "regex": {
+            "defaultValue": false,
+            "rules": [{ "condition": { "country": { "$regex": "(" } }, "force": true }]
+        },
+        "not-regex": {
+            "defaultValue": false,
+            "rules": [{ "condition": { "country": { "$notRegex": "(" } }, "force": true }]
+        },
+        "unknown": {
+            "defaultValue": false,
+            "rules": [{ "condition": { "country": { "$unknown": "BR" } }, "force": true }]
+        }
+    }))
+    .await;
+    let user_attributes = attributes(json!({ "country": "BR" }));
+
+    assert!(client.is_off("regex", user_attributes.clone()));
+    // Nothing matches an invalid pattern, so every value passes `$notRegex`.
+    assert!(client.is_on("not-regex", user_attributes.clone()));
+    assert!(client.is_off("unknown", user_attributes));
+}
+
+#[tokio::test]
+async fn valid_conditions_evaluate() {
+    let client = client(json!({
+        "markets": {
+            "defaultValue": false,
+            "rules": [{ "condition": { "country": { "$in": ["BR", "US"] }, "version": { "$vgt": "1.2.0" } }, "force": true }]
+        }
+    }))
+    .await;
+
+    assert!(client.snapshot().condition_errors().is_empty());
+    assert!(client.is_on("markets", attributes(json!({ "country": "US", "version": "1.10.0" }))));
+    assert!(client.is_off("markets", attributes(json!({ "country": "US", "version": "1.2.0" }))));
+    assert!(client.is_off("markets", attributes(json!({ "country": "AR", "version": "1.10.0" }))));
+}
+
+#[test]
+fn serializes_the_condition_as_written() {
+    let source = json!({ "$or": [{ "country": { "$regexi": "^b" } }, { "plan": { "$nin": ["free"] } }] });
+    let feature: GrowthBookFeature = serde_json::from_value(json!({
+        "defaultValue": true,
+        "rules": [{ "condition": source.clone(), "force": false }]
+    }))
+    .unwrap();
+
+    let written = serde_json::to_value(&feature).unwrap();
+    assert_eq!(written["rules"][0]["condition"], source);
+
+    let condition = Condition::compile(source.clone());
+    assert!(condition.errors().is_empty());
+    assert_eq!(condition.source(), &source);
+}
+
+#[tokio::test]
+async fn inline_experiment_conditions_compile_when_deserialized() {
+    let client = client(json!({})).await;
+    let experiment: Experiment = serde_json::from_value(json!({
+        "key": "my-test",
+        "variations": ["control", "treatment"],
+        "condition": { "country": { "$in": ["BR", "US"] }, "plan": { "$regex": "(" } }
+    }))
+    .unwrap();
+
+    let condition = experiment.condition.as_ref().expect("Missing condition");
+    assert_eq!(condition.errors().len(), 1);
+    // The invalid `$regex` part never matches, so no one is in the experiment.
+    assert!(!client.run(&experiment, attributes(json!({ "id": "1", "country": "BR", "plan": "pro" }))).in_experiment);
+
+    let mut experiment = experiment;
+    experiment.condition = Some(Condition::compile(json!({ "country": { "$in": ["BR", "US"] } })));
+    assert!(client.run(&experiment, attributes(json!({ "id": "1", "country": "BR" }))).in_experiment);
+    assert!(!client.run(&experiment, attributes(json!({ "id": "1", "country": "AR" }))).in_experiment);
+}
+
+#[test]
+#[allow(deprecated)]
+fn source_accessors_are_kept() {
+    let feature: GrowthBookFeature = serde_json::from_value(json!({
+        "defaultValue": true,
+        "rules": [{ "condition": { "country": "BR" }, "force": false }]
+    }))
+    .unwrap();
+    let GrowthBookFeatureRuleKind::Force(rule) = &feature.rules.as_ref().unwrap()[0].kind else {
+        panic!("Expected a force rule");
+    };
+
+    let expected = option_map_to_attributes(Some([(String::from("country"), json!("BR"))].into_iter().collect()));
+    assert_eq!(rule.conditions(), expected);
+
+    let mut experiment = Experiment::new("my-test", vec![json!("control"), json!("treatment")]);
+    assert_eq!(experiment.condition_source(), None);
+    experiment.condition = Some(Condition::compile(json!({ "country": "BR" })));
+    assert_eq!(experiment.condition_source(), Some(&json!({ "country": "BR" })));
+}
@@ -0,0 +1,73 @@
+use std::sync::{Arc, Mutex};
+
+use growthbook_rust::client::{GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use serde_json::json;
+
+fn features() -> serde_json::Value {
+    // id=1 hashes into variation 1 ("treatment") of "my-test" on its own.
+    json!({
+        "checkout": {
+            "defaultValue": "default",
+            "rules": [{ "key": "my-test", "variations": ["control", "treatment"] }]
+        }
+    })
+}
+
+fn attributes(url: &str) -> Option<Vec<GrowthBookAttribute>> {
+    Some(GrowthBookAttribute::from(json!({ "id": "1", "url": url })).expect("Failed to create attributes"))
+}
+
+#[tokio::test]
+async fn query_string_forces_experiment_rule_variation() {
+    let client = GrowthBookClientBuilder::new()
+        .features_json(features())
+        .unwrap()
+        .query_string_overrides(true)
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let result = client.feature_result("checkout", attributes("https://shop.example.com/cart?my-test=0#top"));
+    assert_eq!(result.value, json!("control"));
+
+    // Relative URLs (path + query only) are accepted too.
+    let result = client.feature_result("checkout", attributes("/cart?utm_source=ad&my-test=0"));
+    assert_eq!(result.value, json!("control"));
+
+    // An out-of-range override is ignored and normal bucketing applies.
+    let result = client.feature_result("checkout", attributes("/cart?my-test=7"));
+    assert_eq!(result.value, json!("treatment"));
+}
+
+#[tokio::test]
+async fn query_string_overrides_are_off_by_default() {
+    let client = GrowthBookClientBuilder::new().features_json(features()).unwrap().build().await.expect("Failed to build client");
+
+    let result = client.feature_result("checkout", attributes("/cart?my-test=0"));
+    assert_eq!(result.value, json!("treatment"));
+}
+
+#[tokio::test]
+async fn query_string_variation_is_not_tracked() {
+    let views = Arc::new(Mutex::new(Vec::new()));
+    let viewed = Arc::clone(&views);
+    let client = GrowthBookClientBuilder::new()
+        .features_json(features())
+        .unwrap()
+        .query_string_overrides(true)
+        .on_experiment_viewed(Box::new(move |result| viewed.lock().unwrap().push(result.variation_id)))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let result = client.feature_result("checkout", attributes("/cart?my-test=1"));
+    let experiment_result = result.experiment_result.expect("Missing experiment result");
+    assert!(experiment_result.in_experiment);
+    assert!(!experiment_result.hash_used);
+    assert!(views.lock().unwrap().is_empty());
+
+    // Without the override the user is hashed in and tracked.
+    client.feature_result("checkout", attributes("/cart"));
+    assert_eq!(*views.lock().unwrap(), vec![1]);
+}
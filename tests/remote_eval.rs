@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use serde_json::{json, Value};
+use uuid::Uuid;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn attributes(value: Value) -> HashMap<String, GrowthBookAttribute> {
+    GrowthBookAttribute::from(value)
+        .expect("Failed to create attributes")
+        .into_iter()
+        .map(|attribute| (attribute.key.clone(), attribute))
+        .collect()
+}
+
+async fn mock_remote_eval(
+    mock_server: &MockServer,
+    sdk_key: Uuid,
+    request: Value,
+    value: &str,
+) {
+    Mock::given(method("POST"))
+        .and(path(format!("/api/eval/{sdk_key}")))
+        .and(body_partial_json(request))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "features": { "greeting": { "defaultValue": value } }
+        })))
+        .expect(1)
+        .mount(mock_server)
+        .await;
+}
+
+async fn client(
+    mock_server: &MockServer,
+    sdk_key: Uuid,
+) -> GrowthBookClient {
+    GrowthBookClientBuilder::new()
+        .api_url(mock_server.uri())
+        .client_key(sdk_key.to_string())
+        .remote_eval(true)
+        .attributes(attributes(json!({ "id": "1", "url": "https://example.com/home" })))
+        .forced_variations(HashMap::from([(String::from("checkout-exp"), 1)]))
+        .build()
+        .await
+        .expect("Failed to build client")
+}
+
+#[tokio::test]
+async fn remote_eval_posts_attributes_and_forced_variations() {
+    let mock_server = MockServer::start().await;
+    let sdk_key = Uuid::now_v7();
+    mock_remote_eval(
+        &mock_server,
+        sdk_key,
+        json!({
+            "attributes": { "id": "1", "url": "https://example.com/home" },
+            "forcedVariations": { "checkout-exp": 1 },
+            "url": "https://example.com/home"
+        }),
+        "hello user 1",
+    )
+    .await;
+
+    let client = client(&mock_server, sdk_key).await;
+
+    assert_eq!(client.get_string("greeting", "", None), "hello user 1");
+}
+
+#[tokio::test]
+async fn remote_eval_refetches_when_attributes_change() {
+    let mock_server = MockServer::start().await;
+    let sdk_key = Uuid::now_v7();
+    mock_remote_eval(&mock_server, sdk_key, json!({ "attributes": { "id": "1" } }), "hello user 1").await;
+    mock_remote_eval(&mock_server, sdk_key, json!({ "attributes": { "id": "2" } }), "hello user 2").await;
+
+    let client = client(&mock_server, sdk_key).await;
+    assert_eq!(client.get_string("greeting", "", None), "hello user 1");
+
+    client.set_attributes(attributes(json!({ "id": "2", "url": "https://example.com/home" }))).await;
+    assert_eq!(client.get_string("greeting", "", None), "hello user 2");
+
+    // Unchanged attributes don't re-fetch, and a known attribute set is
+    // served from the cache: each mock expects exactly one request.
+    client.set_attributes(attributes(json!({ "id": "2", "url": "https://example.com/home" }))).await;
+    client.set_attributes(attributes(json!({ "id": "1", "url": "https://example.com/home" }))).await;
+    assert_eq!(client.get_string("greeting", "", None), "hello user 1");
+}
+
+#[tokio::test]
+async fn remote_eval_requires_network_config() {
+    let result = GrowthBookClientBuilder::new().features_json(json!({})).unwrap().remote_eval(true).build().await;
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,43 @@
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use serde_json::json;
+
+fn attributes(id: &str) -> Option<Vec<GrowthBookAttribute>> {
+    Some(GrowthBookAttribute::from(json!({ "id": id })).expect("Failed to create attributes"))
+}
+
+async fn client(rule: serde_json::Value) -> GrowthBookClient {
+    GrowthBookClientBuilder::new()
+        .features_json(json!({ "new-ui": { "defaultValue": false, "rules": [rule] } }))
+        .unwrap()
+        .build()
+        .await
+        .expect("Failed to build client")
+}
+
+// With hashVersion 2, id=3 hashes to 0.9143 on "new-ui" but 0.1352 on
+// "shared-seed"; id=2 hashes to 0.3096 and 0.9604.
+#[tokio::test]
+async fn rollout_hashes_on_rule_seed() {
+    let client = client(json!({ "force": true, "coverage": 0.5, "seed": "shared-seed", "hashVersion": 2 })).await;
+
+    assert!(client.is_on("new-ui", attributes("3")));
+    assert!(client.is_off("new-ui", attributes("2")));
+}
+
+// id=2 hashes to 0.0233 on "holdout-ns", id=1 to 0.5486.
+#[tokio::test]
+async fn rollout_applies_filters() {
+    let client = client(json!({
+        "force": true,
+        "coverage": 1.0,
+        "filters": [{ "seed": "holdout-ns", "ranges": [[0.0, 0.5]], "hashVersion": 2 }]
+    }))
+    .await;
+
+    assert!(client.is_on("new-ui", attributes("2")));
+
+    let result = client.feature_result("new-ui", attributes("1"));
+    assert!(result.off);
+    assert_eq!(result.source, "defaultValue");
+}
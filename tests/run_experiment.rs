@@ -0,0 +1,64 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use growthbook_rust::client::GrowthBookClientBuilder;
+use growthbook_rust::model_public::{Experiment, GrowthBookAttribute};
+use serde_json::json;
+
+fn experiment(active: Option<bool>) -> Experiment {
+    let mut experiment = Experiment::new("my-test", vec![json!("control"), json!("treatment")]);
+    experiment.active = active;
+    experiment
+}
+
+// Inline experiments bucket exactly like the `run` corpus ("default weights - 1"
+// puts id=1 in variation 1) and fire `on_experiment_viewed` for the exposure.
+#[tokio::test]
+async fn run_buckets_user_and_tracks_exposure() {
+    let viewed = Arc::new(AtomicUsize::new(0));
+    let viewed_in_callback = viewed.clone();
+
+    let client = GrowthBookClientBuilder::new()
+        .features_json(json!({}))
+        .unwrap()
+        .on_experiment_viewed(Box::new(move |result| {
+            assert_eq!(result.experiment_key, "my-test");
+            viewed_in_callback.fetch_add(1, Ordering::SeqCst);
+        }))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let attributes = GrowthBookAttribute::from(json!({ "id": "1" })).expect("Failed to create attributes");
+    let result = client.run(&experiment(None), Some(attributes));
+
+    assert!(result.in_experiment);
+    assert!(result.hash_used);
+    assert_eq!(result.variation_id, 1);
+    assert_eq!(result.value, json!("treatment"));
+    assert_eq!(viewed.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn run_inactive_experiment_returns_control_without_tracking() {
+    let viewed = Arc::new(AtomicUsize::new(0));
+    let viewed_in_callback = viewed.clone();
+
+    let client = GrowthBookClientBuilder::new()
+        .features_json(json!({}))
+        .unwrap()
+        .on_experiment_viewed(Box::new(move |_| {
+            viewed_in_callback.fetch_add(1, Ordering::SeqCst);
+        }))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let attributes = GrowthBookAttribute::from(json!({ "id": "1" })).expect("Failed to create attributes");
+    let result = client.run(&experiment(Some(false)), Some(attributes));
+
+    assert!(!result.in_experiment);
+    assert_eq!(result.variation_id, 0);
+    assert_eq!(result.value, json!("control"));
+    assert_eq!(viewed.load(Ordering::SeqCst), 0);
+}
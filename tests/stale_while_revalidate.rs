@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use serde_json::json;
+use uuid::Uuid;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn payload(enabled: bool) -> serde_json::Value {
+    json!({ "features": { "dark-mode": { "defaultValue": enabled } } })
+}
+
+async fn client(
+    mock_server: &MockServer,
+    sdk_key: Uuid,
+) -> GrowthBookClient {
+    GrowthBookClientBuilder::new()
+        .api_url(mock_server.uri())
+        .client_key(sdk_key.to_string())
+        .ttl(Duration::from_millis(100))
+        .stale_while_revalidate(true)
+        .build()
+        .await
+        .expect("Failed to build client")
+}
+
+#[tokio::test]
+async fn expired_payload_is_served_while_revalidating() {
+    let mock_server = MockServer::start().await;
+    let sdk_key = Uuid::now_v7();
+    Mock::given(method("GET"))
+        .and(path(format!("/api/features/{sdk_key}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(payload(false)))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/api/features/{sdk_key}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(payload(true)).set_delay(Duration::from_millis(300)))
+        .mount(&mock_server)
+        .await;
+
+    let client = client(&mock_server, sdk_key).await;
+    assert!(!client.is_stale());
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    // The refresh doesn't wait for the slow response.
+    tokio::time::timeout(Duration::from_millis(100), client.refresh()).await.expect("refresh waited on the network");
+    assert!(client.is_stale());
+    assert!(!client.is_on("dark-mode", None));
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(!client.is_stale());
+    assert!(client.is_on("dark-mode", None));
+}
+
+#[tokio::test]
+async fn stale_payload_is_kept_while_api_is_down() {
+    let mock_server = MockServer::start().await;
+    let sdk_key = Uuid::now_v7();
+    Mock::given(method("GET"))
+        .and(path(format!("/api/features/{sdk_key}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(payload(true)))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/api/features/{sdk_key}")))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+
+    let client = client(&mock_server, sdk_key).await;
+    for _ in 0..3 {
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        client.refresh().await;
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert!(client.is_stale());
+    assert!(client.is_on("dark-mode", None));
+}
@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use growthbook_rust::client::{GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::GrowthBookAttribute;
+use growthbook_rust::sticky_bucket::{copy_documents, parse_assignment_key, InMemoryStickyBucketService, StickyAssignmentsDocument, StickyBucketAdmin, StickyBucketService};
+use serde_json::json;
+
+fn document(
+    attribute_name: &str,
+    attribute_value: &str,
+    assignments: &[(&str, &str)],
+) -> StickyAssignmentsDocument {
+    StickyAssignmentsDocument::new(attribute_name, attribute_value, assignments.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect())
+}
+
+fn documents() -> Vec<StickyAssignmentsDocument> {
+    vec![
+        document("id", "1", &[("exp__0", "0"), ("exp__1", "1"), ("other__0", "1"), ("exp__legacy", "0")]),
+        document("id", "2", &[("exp__1", "1")]),
+        document("deviceId", "d", &[("exp__1", "0")]),
+    ]
+}
+
+fn counts(entries: &[(&str, usize)]) -> BTreeMap<String, usize> {
+    entries.iter().map(|(variation, count)| (variation.to_string(), *count)).collect()
+}
+
+/// Runs the admin operations against an empty `service`.
+fn verify_admin_operations(service: &dyn StickyBucketAdmin) {
+    service.import_documents(documents()).unwrap();
+
+    assert_eq!(service.variation_counts("exp", 1, "id").unwrap(), counts(&[("1", 2)]));
+    assert_eq!(service.variation_counts("exp", 1, "deviceId").unwrap(), counts(&[("0", 1)]));
+    assert_eq!(service.variation_counts("exp", 0, "id").unwrap(), counts(&[("0", 1)]));
+    assert!(service.variation_counts("missing", 0, "id").unwrap().is_empty());
+
+    // Importing replaces a document instead of merging into it.
+    service.import_documents(vec![document("id", "2", &[("exp__1", "0")])]).unwrap();
+    assert_eq!(service.get_assignments("id", "2").unwrap(), document("id", "2", &[("exp__1", "0")]));
+
+    assert_eq!(service.delete_versions_below("exp", 1).unwrap(), 1);
+    assert_eq!(service.get_assignments("id", "1").unwrap().assignments.len(), 3);
+    assert_eq!(service.delete_experiment("other").unwrap(), 1);
+    assert_eq!(service.delete_experiment("exp").unwrap(), 3);
+
+    // Keys without a bucket version are left alone, and emptied documents
+    // are removed.
+    let remaining = service.all_documents().unwrap();
+    assert_eq!(remaining, vec![document("id", "1", &[("exp__legacy", "0")])]);
+}
+
+fn verify_export_import(
+    from: &dyn StickyBucketAdmin,
+    to: &dyn StickyBucketAdmin,
+) {
+    from.import_documents(documents()).unwrap();
+
+    let exported = from.export_json().unwrap();
+
+    let parsed: serde_json::Value = serde_json::from_str(&exported).unwrap();
+    assert_eq!(parsed[0], json!({ "attributeName": "deviceId", "attributeValue": "d", "assignments": { "exp__1": "0" } }));
+    assert_eq!(to.import_json(&exported).unwrap(), 3);
+    assert_eq!(to.export_json().unwrap(), exported);
+}
+
+#[test]
+fn in_memory_admin_operations() {
+    verify_admin_operations(&InMemoryStickyBucketService::new());
+}
+
+#[test]
+fn in_memory_export_import() {
+    verify_export_import(&InMemoryStickyBucketService::new(), &InMemoryStickyBucketService::new());
+}
+
+#[test]
+fn copy_documents_between_stores() {
+    let from = InMemoryStickyBucketService::from_documents(documents());
+    let to = InMemoryStickyBucketService::new();
+
+    assert_eq!(copy_documents(&from, &to).unwrap(), 3);
+
+    let copied: HashMap<String, StickyAssignmentsDocument> = to.all_documents().unwrap().into_iter().map(|doc| (doc.key(), doc)).collect();
+    assert_eq!(copied.len(), 3);
+    assert_eq!(copied["id||1"], documents()[0]);
+}
+
+#[tokio::test]
+async fn deleting_old_versions_re_enrolls_blocked_users() {
+    let service = Arc::new(InMemoryStickyBucketService::from_documents([document("id", "1", &[("exp__0", "0")])]));
+    let features = json!({
+        "feature": {
+            "defaultValue": "default",
+            "rules": [{ "key": "exp", "variations": ["control", "treatment"], "bucketVersion": 1, "minBucketVersion": 1 }]
+        }
+    });
+    let client = GrowthBookClientBuilder::new()
+        .features_json(features)
+        .unwrap()
+        .sticky_bucket_service(service.clone())
+        .build()
+        .await
+        .expect("Failed to build client");
+    let user = || Some(GrowthBookAttribute::from(json!({ "id": "1" })).expect("Failed to create attributes"));
+
+    let result = client.feature_result("feature", user());
+    assert_eq!(result.source, "defaultValue");
+
+    assert_eq!(service.delete_versions_below("exp", 1).unwrap(), 1);
+
+    let result = client.feature_result("feature", user());
+    assert_eq!(result.source, "experiment");
+    assert!(service.get_assignments("id", "1").unwrap().assignments.contains_key("exp__1"));
+}
+
+#[test]
+fn import_json_rejects_malformed_dump() {
+    let service = InMemoryStickyBucketService::new();
+
+    assert!(service.import_json("{\"id||1\": {}}").is_err());
+    assert!(service.all_documents().unwrap().is_empty());
+}
+
+#[test]
+fn parses_assignment_keys() {
+    assert_eq!(parse_assignment_key("exp__2"), Some(("exp", 2)));
+    assert_eq!(parse_assignment_key("my__exp__10"), Some(("my__exp", 10)));
+    assert_eq!(parse_assignment_key("exp__legacy"), None);
+    assert_eq!(parse_assignment_key("exp"), None);
+}
+
+#[cfg(feature = "sticky-bucket-file")]
+mod json_file {
+    use super::*;
+    use growthbook_rust::sticky_bucket::JsonFileStickyBucketService;
+    use std::path::PathBuf;
+
+    fn temp_directory() -> PathBuf {
+        std::env::temp_dir().join(format!("growthbook-sticky-bucket-admin-{}", uuid::Uuid::now_v7()))
+    }
+
+    #[test]
+    fn admin_operations() {
+        let directory = temp_directory();
+        verify_admin_operations(&JsonFileStickyBucketService::new(directory.join("sticky.json")));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn export_import() {
+        let directory = temp_directory();
+        verify_export_import(&InMemoryStickyBucketService::new(), &JsonFileStickyBucketService::new(directory.join("sticky.json")));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
+
+#[cfg(feature = "sticky-bucket-sqlite")]
+mod sqlite {
+    use super::*;
+    use growthbook_rust::sticky_bucket::SqliteStickyBucketService;
+
+    #[test]
+    fn admin_operations() {
+        verify_admin_operations(&SqliteStickyBucketService::open_in_memory().unwrap());
+    }
+
+    #[test]
+    fn export_import() {
+        verify_export_import(&SqliteStickyBucketService::open_in_memory().unwrap(), &SqliteStickyBucketService::open_in_memory().unwrap());
+    }
+}
@@ -4,7 +4,7 @@ use std::sync::Arc;
 use growthbook_rust::dto::GrowthBookFeature;
 use growthbook_rust::growthbook::GrowthBook;
 use growthbook_rust::model_public::{GrowthBookAttribute, GrowthBookAttributeValue};
-use growthbook_rust::sticky_bucket::{InMemoryStickyBucketService, StickyBucketService};
+use growthbook_rust::sticky_bucket::{InMemoryStickyBucketService, StickyAssignmentsDocument, StickyBucketService};
 use serde_json::json;
 
 // #20: when a user already has a sticky-bucket assignment, JS runExperiment
@@ -22,7 +22,7 @@ async fn sticky_assignment_wins_over_unmatched_condition() {
     // Pre-seed: rule key "exp1", bucketVersion 0 -> "exp1__0" -> variation 1.
     let mut seed = HashMap::new();
     seed.insert("exp1__0".to_string(), "1".to_string());
-    service.save_assignments("id", "abc", seed);
+    service.save_assignments(StickyAssignmentsDocument::new("id", "abc", seed));
 
     let features: HashMap<String, GrowthBookFeature> = serde_json::from_value(json!({
         "f": {
@@ -39,9 +39,8 @@ async fn sticky_assignment_wins_over_unmatched_condition() {
     let gb = GrowthBook {
         forced_variations: None,
         features,
-        attributes: None,
         sticky_bucket_service: Some(service.clone()),
-        saved_groups: Default::default(),
+        ..Default::default()
     };
 
     // User has `id` (the hash attribute) but no `country`, so the condition fails.
@@ -1,13 +1,14 @@
 use serde::Deserialize;
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::fs;
 use std::sync::Arc;
 
+use growthbook_rust::client::{GrowthBookClientBuilder, GrowthBookClientTrait};
 use growthbook_rust::dto::GrowthBookFeature;
 use growthbook_rust::growthbook::GrowthBook;
 use growthbook_rust::model_public::{FeatureResult, GrowthBookAttribute, GrowthBookAttributeValue};
-use growthbook_rust::sticky_bucket::{InMemoryStickyBucketService, StickyBucketService};
+use growthbook_rust::sticky_bucket::{InMemoryStickyBucketService, StickyAssignmentsDocument, StickyBucketService};
 
 // -----------------------------------------------------------------------------
 // Data Structures
@@ -24,10 +25,10 @@ struct AllCases {
 struct TestCase {
     name: String,
     context: TestContext,
-    existing_assignments: Vec<StickyAssignment>,
+    existing_assignments: Vec<StickyAssignmentsDocument>,
     feature_key: String,
     expected_result: Option<ExpectedResult>,
-    expected_sticky_assignments: HashMap<String, StickyAssignmentDoc>,
+    expected_sticky_assignments: HashMap<String, StickyAssignmentsDocument>,
 }
 
 #[derive(Deserialize)]
@@ -36,26 +37,6 @@ struct TestContext {
     features: HashMap<String, GrowthBookFeature>,
 }
 
-#[derive(Deserialize)]
-struct StickyAssignment {
-    #[serde(rename = "attributeName")]
-    attribute_name: String,
-    #[serde(rename = "attributeValue")]
-    attribute_value: String,
-    assignments: HashMap<String, String>,
-}
-
-#[derive(Deserialize)]
-struct StickyAssignmentDoc {
-    #[allow(dead_code)]
-    #[serde(rename = "attributeName")]
-    attribute_name: String,
-    #[allow(dead_code)]
-    #[serde(rename = "attributeValue")]
-    attribute_value: String,
-    assignments: HashMap<String, String>,
-}
-
 #[derive(Deserialize)]
 struct ExpectedResult {
     value: Value,
@@ -83,10 +64,10 @@ fn parse_test_case(case_value: &Value) -> TestCase {
     // Manual parsing because the top-level array has mixed types
     let name = array[0].as_str().unwrap().to_string();
     let context: TestContext = serde_json::from_value(array[1].clone()).unwrap();
-    let existing_assignments: Vec<StickyAssignment> = serde_json::from_value(array[2].clone()).unwrap();
+    let existing_assignments: Vec<StickyAssignmentsDocument> = serde_json::from_value(array[2].clone()).unwrap();
     let feature_key = array[3].as_str().unwrap().to_string();
     let expected_result: Option<ExpectedResult> = serde_json::from_value(array[4].clone()).unwrap();
-    let expected_sticky_assignments: HashMap<String, StickyAssignmentDoc> = serde_json::from_value(array[5].clone()).unwrap();
+    let expected_sticky_assignments: HashMap<String, StickyAssignmentsDocument> = serde_json::from_value(array[5].clone()).unwrap();
 
     TestCase {
         name,
@@ -99,12 +80,25 @@ fn parse_test_case(case_value: &Value) -> TestCase {
 }
 
 /// Sets up the sticky bucket service and populates it with existing assignments.
-fn load_sticky_bucket_service(assignments: &[StickyAssignment]) -> Arc<InMemoryStickyBucketService> {
-    let service = Arc::new(InMemoryStickyBucketService::new());
+fn load_sticky_bucket_service(assignments: &[StickyAssignmentsDocument]) -> Arc<dyn StickyBucketService> {
+    Arc::new(InMemoryStickyBucketService::from_documents(assignments.iter().cloned()))
+}
+
+/// Populates `service` with existing assignments.
+#[cfg(any(feature = "sticky-bucket-file", feature = "sticky-bucket-sqlite"))]
+fn seed<S: StickyBucketService + 'static>(
+    service: S,
+    assignments: &[StickyAssignmentsDocument],
+) -> Arc<dyn StickyBucketService> {
     for assignment in assignments {
-        service.save_assignments(&assignment.attribute_name, &assignment.attribute_value, assignment.assignments.clone());
+        service.save_assignments(assignment.clone());
     }
-    service
+    Arc::new(service)
+}
+
+#[cfg(any(feature = "sticky-bucket-file", feature = "sticky-bucket-sqlite"))]
+fn temp_directory() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("growthbook-sticky-bucket-{}", uuid::Uuid::now_v7()))
 }
 
 /// Verifies that the actual feature result matches expectations.
@@ -131,19 +125,17 @@ fn verify_feature_result(
 fn verify_assignments(
     case_name: &str,
     service: &dyn StickyBucketService,
-    expected_docs: &HashMap<String, StickyAssignmentDoc>,
+    expected_docs: &HashMap<String, StickyAssignmentsDocument>,
 ) {
     for (doc_key, doc) in expected_docs {
-        let parts: Vec<&str> = doc_key.split("||").collect();
-        let attr_name = parts[0];
-        let attr_value = parts[1];
+        assert_eq!(*doc_key, doc.key(), "Document key mismatch in case {}", case_name);
 
         if !doc.assignments.is_empty() {
             let actual = service
-                .get_assignments(attr_name, attr_value)
+                .get_assignments(&doc.attribute_name, &doc.attribute_value)
                 .unwrap_or_else(|| panic!("Expected assignments for {} in case {}", doc_key, case_name));
 
-            assert_eq!(actual, doc.assignments, "Assignment mismatch for {} in case {}", doc_key, case_name);
+            assert_eq!(actual, *doc, "Assignment mismatch for {} in case {}", doc_key, case_name);
         }
     }
 }
@@ -152,13 +144,13 @@ fn verify_assignments(
 // Main Test Runner
 // -----------------------------------------------------------------------------
 
-#[test]
-fn test_sticky_bucket_scenarios() {
+/// Runs every `stickyBucket` case against the services `load_service` creates.
+fn run_sticky_bucket_scenarios(load_service: impl Fn(&[StickyAssignmentsDocument]) -> Arc<dyn StickyBucketService>) {
     let cases = load_test_cases();
 
     for case in cases {
         println!("Running test case: {}", case.name);
-        let service = load_sticky_bucket_service(&case.existing_assignments);
+        let service = load_service(&case.existing_assignments);
         // Prepare User Attributes
         let mut user_attrs = Vec::new();
         for (k, v) in case.context.attributes {
@@ -169,9 +161,8 @@ fn test_sticky_bucket_scenarios() {
         let gb = GrowthBook {
             forced_variations: None,
             features: case.context.features,
-            attributes: None,
             sticky_bucket_service: Some(service.clone()),
-            saved_groups: Default::default(),
+            ..Default::default()
         };
 
         // Execute Check
@@ -182,3 +173,173 @@ fn test_sticky_bucket_scenarios() {
         verify_assignments(&case.name, service.as_ref(), &case.expected_sticky_assignments);
     }
 }
+
+#[test]
+fn test_sticky_bucket_scenarios() {
+    run_sticky_bucket_scenarios(load_sticky_bucket_service);
+}
+
+#[cfg(feature = "sticky-bucket-file")]
+#[test]
+fn test_sticky_bucket_scenarios_json_file() {
+    use growthbook_rust::sticky_bucket::JsonFileStickyBucketService;
+
+    let directory = temp_directory();
+    run_sticky_bucket_scenarios(|assignments| seed(JsonFileStickyBucketService::new(directory.join(format!("{}.json", uuid::Uuid::now_v7()))), assignments));
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[cfg(feature = "sticky-bucket-sqlite")]
+#[test]
+fn test_sticky_bucket_scenarios_sqlite() {
+    use growthbook_rust::sticky_bucket::SqliteStickyBucketService;
+
+    run_sticky_bucket_scenarios(|assignments| seed(SqliteStickyBucketService::open_in_memory().unwrap(), assignments));
+}
+
+/// Saves `writers` x 10 assignments into the same document concurrently, each
+/// writer with its own service (as separate processes would), and checks
+/// none are lost.
+#[cfg(any(feature = "sticky-bucket-file", feature = "sticky-bucket-sqlite"))]
+fn verify_concurrent_writers<S: StickyBucketService + 'static>(
+    writers: usize,
+    open: impl Fn() -> S + Sync,
+) {
+    std::thread::scope(|scope| {
+        for writer in 0..writers {
+            let service = open();
+            scope.spawn(move || {
+                for experiment in 0..10 {
+                    let assignments = HashMap::from([(format!("exp-{writer}-{experiment}__0"), experiment.to_string())]);
+                    service.save_assignments(StickyAssignmentsDocument::new("id", "1", assignments));
+                }
+            });
+        }
+    });
+
+    let doc = open().get_assignments("id", "1").expect("document");
+    assert_eq!(doc.assignments.len(), writers * 10);
+    assert_eq!(doc.assignments["exp-3-7__0"], "7");
+}
+
+#[cfg(feature = "sticky-bucket-file")]
+#[test]
+fn test_json_file_concurrent_writers() {
+    use growthbook_rust::sticky_bucket::JsonFileStickyBucketService;
+
+    let directory = temp_directory();
+    let path = directory.join("sticky.json");
+    verify_concurrent_writers(8, || JsonFileStickyBucketService::new(&path));
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[cfg(feature = "sticky-bucket-file")]
+#[test]
+fn test_json_file_is_js_document_map_and_not_clobbered_when_unreadable() {
+    use growthbook_rust::sticky_bucket::JsonFileStickyBucketService;
+
+    let directory = temp_directory();
+    let path = directory.join("sticky.json");
+    let service = JsonFileStickyBucketService::new(&path);
+    let doc = StickyAssignmentsDocument::new("id", "1", HashMap::from([("exp__0".to_string(), "1".to_string())]));
+    service.save_assignments(doc.clone());
+
+    let stored: HashMap<String, StickyAssignmentsDocument> = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+    assert_eq!(stored, HashMap::from([("id||1".to_string(), doc.clone())]));
+
+    fs::write(&path, "not json").unwrap();
+    service.save_assignments(doc);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "not json");
+    assert!(service.get_assignments("id", "1").is_none());
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[cfg(feature = "sticky-bucket-sqlite")]
+#[test]
+fn test_sqlite_concurrent_writers() {
+    use growthbook_rust::sticky_bucket::SqliteStickyBucketService;
+
+    let directory = temp_directory();
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("sticky.sqlite");
+    verify_concurrent_writers(8, || SqliteStickyBucketService::open(&path).unwrap());
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn test_sticky_assignments_document_wire_format() {
+    let doc = StickyAssignmentsDocument::new("id", "123", HashMap::from([("exp__0".to_string(), "1".to_string())]));
+
+    let json = serde_json::to_value(&doc).unwrap();
+
+    assert_eq!(json, serde_json::json!({ "attributeName": "id", "attributeValue": "123", "assignments": { "exp__0": "1" } }));
+    assert_eq!(serde_json::from_value::<StickyAssignmentsDocument>(json).unwrap(), doc);
+}
+
+#[test]
+fn test_sticky_assignments_document_legacy_conversion() {
+    let assignments = HashMap::from([("exp__0".to_string(), "1".to_string())]);
+
+    let doc = StickyAssignmentsDocument::from_legacy("deviceId||a||b", assignments.clone()).unwrap();
+
+    assert_eq!(doc.attribute_name, "deviceId");
+    assert_eq!(doc.attribute_value, "a||b");
+    assert_eq!(doc.clone().into_legacy(), ("deviceId||a||b".to_string(), assignments.clone()));
+    assert!(StickyAssignmentsDocument::from_legacy("deviceId", assignments).is_none());
+}
+
+#[test]
+fn test_get_all_assignments_is_keyed_by_document_key() {
+    let service = InMemoryStickyBucketService::from_documents([
+        StickyAssignmentsDocument::new("id", "1", HashMap::from([("exp__0".to_string(), "0".to_string())])),
+        StickyAssignmentsDocument::new("deviceId", "d", HashMap::from([("exp__0".to_string(), "1".to_string())])),
+    ]);
+    let attributes = HashMap::from([("id".to_string(), "1".to_string()), ("deviceId".to_string(), "other".to_string())]);
+
+    let docs = service.get_all_assignments(&attributes);
+
+    assert_eq!(docs.len(), 1);
+    assert_eq!(docs["id||1"].assignments["exp__0"], "0");
+}
+
+#[tokio::test]
+async fn test_assignments_store_meta_keys() {
+    let service = Arc::new(InMemoryStickyBucketService::from_documents([
+        StickyAssignmentsDocument::new("id", "2", HashMap::from([("meta-test__0".to_string(), "ctl".to_string())])),
+        // Saved before meta keys were stored: the variation's index.
+        StickyAssignmentsDocument::new("id", "3", HashMap::from([("meta-test__0".to_string(), "0".to_string())])),
+    ]));
+    // Every hashed user lands in "treatment".
+    let features = json!({
+        "feature": {
+            "defaultValue": "default",
+            "rules": [{
+                "key": "meta-test",
+                "variations": ["control", "treatment"],
+                "weights": [0, 1],
+                "meta": [{ "key": "ctl" }, { "key": "trt" }]
+            }]
+        }
+    });
+    let client = GrowthBookClientBuilder::new()
+        .features_json(features)
+        .unwrap()
+        .sticky_bucket_service(service.clone())
+        .build()
+        .await
+        .expect("Failed to build client");
+    let user = |id: &str| Some(GrowthBookAttribute::from(json!({ "id": id })).expect("Failed to create attributes"));
+
+    let result = client.feature_result("feature", user("1"));
+    assert_eq!(result.value, json!("treatment"));
+    assert_eq!(service.get_assignments("id", "1").unwrap().assignments["meta-test__0"], "trt");
+
+    let result = client.feature_result("feature", user("2"));
+    assert_eq!(result.value, json!("control"));
+    assert!(result.experiment_result.unwrap().sticky_bucket_used);
+
+    let result = client.feature_result("feature", user("3"));
+    assert_eq!(result.value, json!("control"));
+    assert!(result.experiment_result.unwrap().sticky_bucket_used);
+    assert_eq!(service.get_assignments("id", "3").unwrap().assignments["meta-test__0"], "0");
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use serde_json::json;
+use uuid::Uuid;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn payload(enabled: bool) -> serde_json::Value {
+    json!({ "features": { "kill-switch": { "defaultValue": enabled } } })
+}
+
+async fn mock_features(
+    mock_server: &MockServer,
+    sdk_key: Uuid,
+    enabled: bool,
+    times: Option<u64>,
+) {
+    let mock = Mock::given(method("GET"))
+        .and(path(format!("/api/features/{sdk_key}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(payload(enabled)));
+    match times {
+        Some(times) => mock.up_to_n_times(times).mount(mock_server).await,
+        None => mock.mount(mock_server).await,
+    }
+}
+
+async fn streaming_client(
+    builder: GrowthBookClientBuilder,
+    mock_server: &MockServer,
+    sdk_key: Uuid,
+) -> GrowthBookClient {
+    builder
+        .api_url(mock_server.uri())
+        .client_key(sdk_key.to_string())
+        .streaming(true)
+        .refresh_interval(Duration::from_millis(100))
+        .build()
+        .await
+        .expect("Failed to build client")
+}
+
+async fn wait_until_on(client: &GrowthBookClient) -> bool {
+    for _ in 0..50 {
+        if client.is_on("kill-switch", None) {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn streamed_payloads_are_applied() {
+    let mock_server = MockServer::start().await;
+    let sdk_key = Uuid::now_v7();
+    mock_features(&mock_server, sdk_key, false, None).await;
+    let body = format!(": connected\n\nevent: features\ndata: {}\n\n", payload(true));
+    Mock::given(method("GET"))
+        .and(path(format!("/sub/{sdk_key}")))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(&mock_server)
+        .await;
+
+    let client = streaming_client(GrowthBookClientBuilder::new(), &mock_server, sdk_key).await;
+
+    assert!(wait_until_on(&client).await);
+    // The streamed payload is cached, so polling between reconnects keeps it.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(client.is_on("kill-switch", None));
+}
+
+#[tokio::test]
+async fn failed_stream_falls_back_to_polling() {
+    let mock_server = MockServer::start().await;
+    let sdk_key = Uuid::now_v7();
+    mock_features(&mock_server, sdk_key, false, Some(1)).await;
+    mock_features(&mock_server, sdk_key, true, None).await;
+
+    let client = streaming_client(GrowthBookClientBuilder::new().ttl(Duration::from_secs(0)), &mock_server, sdk_key).await;
+    assert!(!client.is_on("kill-switch", None));
+
+    assert!(wait_until_on(&client).await);
+    let requests = mock_server.received_requests().await.expect("Failed to read requests");
+    assert!(requests.iter().any(|request| request.url.path() == format!("/sub/{sdk_key}")));
+}
+
+#[tokio::test]
+async fn streaming_requires_network_config() {
+    let result = GrowthBookClientBuilder::new().features_json(json!({})).unwrap().streaming(true).build().await;
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,69 @@
+use std::sync::{Arc, Mutex};
+
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use serde::Deserialize;
+use serde_json::json;
+
+async fn client(mismatches: Arc<Mutex<Vec<String>>>) -> GrowthBookClient {
+    GrowthBookClientBuilder::new()
+        .features_json(json!({
+            "dark-mode": { "defaultValue": true },
+            "title": { "defaultValue": "Welcome" },
+            "max-items": { "defaultValue": 25 },
+            "ratio": { "defaultValue": 0.75 },
+            "banner": { "defaultValue": { "color": "blue", "size": 3 } },
+            "null-flag": { "defaultValue": null }
+        }))
+        .unwrap()
+        .on_feature_value_mismatch(Box::new(move |feature_name, _, _| {
+            mismatches.lock().unwrap().push(feature_name);
+        }))
+        .build()
+        .await
+        .expect("Failed to build client")
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Banner {
+    color: String,
+    size: i64,
+}
+
+#[tokio::test]
+async fn typed_getters_return_feature_values() {
+    let mismatches = Arc::new(Mutex::new(Vec::new()));
+    let client = client(mismatches.clone()).await;
+
+    assert!(client.get_bool("dark-mode", false, None));
+    assert_eq!(client.get_string("title", "", None), "Welcome");
+    assert_eq!(client.get_i64("max-items", 0, None), 25);
+    assert_eq!(client.get_f64("ratio", 0.0, None), 0.75);
+    assert_eq!(client.get_json("banner", json!({}), None), json!({ "color": "blue", "size": 3 }));
+    assert_eq!(
+        client.get_feature_value("banner", Banner { color: String::new(), size: 0 }, None),
+        Banner { color: String::from("blue"), size: 3 }
+    );
+    assert!(mismatches.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn typed_getters_fall_back_to_default() {
+    let mismatches = Arc::new(Mutex::new(Vec::new()));
+    let client = client(mismatches.clone()).await;
+
+    // Unknown and null features are not mismatches.
+    assert_eq!(client.get_string("unknown", "fallback", None), "fallback");
+    assert_eq!(client.get_i64("null-flag", 7, None), 7);
+    assert!(mismatches.lock().unwrap().is_empty());
+
+    assert_eq!(client.get_i64("title", 7, None), 7);
+    assert!(!client.get_bool("max-items", false, None));
+    assert_eq!(*mismatches.lock().unwrap(), vec!["title", "max-items"]);
+}
+
+#[tokio::test]
+async fn typed_getters_work_through_trait_objects() {
+    let client: Box<dyn GrowthBookClientTrait> = Box::new(client(Arc::new(Mutex::new(Vec::new()))).await);
+
+    assert_eq!(client.get_i64("max-items", 0, None), 25);
+}
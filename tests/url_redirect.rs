@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex};
+
+use growthbook_rust::client::GrowthBookClientBuilder;
+use growthbook_rust::model_public::GrowthBookAttribute;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// Same experiment as the `urlRedirect` corpus: id=1 lands in the redirect
+// variation, id=9 in the control.
+async fn mock_server(sdk_key: &str) -> MockServer {
+    let mock_server = MockServer::start().await;
+    let response_body = json!({
+        "features": {},
+        "experiments": [{
+            "key": "my-experiment",
+            "urlPatterns": [{ "type": "simple", "include": true, "pattern": "http://www.example.com/home" }],
+            "weights": [0.1, 0.9],
+            "variations": [{}, { "urlRedirect": "http://www.example.com/home-new" }],
+            "persistQueryString": true
+        }]
+    });
+
+    Mock::given(method("GET"))
+        .and(path(format!("/api/features/{}", sdk_key)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response_body))
+        .mount(&mock_server)
+        .await;
+
+    mock_server
+}
+
+#[tokio::test]
+async fn get_redirect_url_redirects_and_tracks_exposure() {
+    let sdk_key = "redirect_key";
+    let mock_server = mock_server(sdk_key).await;
+    let viewed = Arc::new(Mutex::new(Vec::new()));
+    let viewed_in_callback = viewed.clone();
+
+    let client = GrowthBookClientBuilder::new()
+        .api_url(mock_server.uri())
+        .client_key(sdk_key.to_string())
+        .on_experiment_viewed(Box::new(move |result| {
+            viewed_in_callback.lock().unwrap().push((result.experiment_key, result.variation_id));
+        }))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let attributes = GrowthBookAttribute::from(json!({ "id": "1" })).expect("Failed to create attributes");
+    let redirect = client.get_redirect_url("http://www.example.com/home?color=blue", Some(attributes)).expect("Expected a redirect");
+
+    assert_eq!(redirect.url_redirect, "http://www.example.com/home-new");
+    assert_eq!(redirect.url_with_params, "http://www.example.com/home-new?color=blue");
+    assert_eq!(redirect.experiment_result.variation_id, 1);
+    assert_eq!(*viewed.lock().unwrap(), vec![(String::from("my-experiment"), 1)]);
+
+    // Pages the experiment doesn't target never redirect or track.
+    let attributes = GrowthBookAttribute::from(json!({ "id": "1" })).expect("Failed to create attributes");
+    assert!(client.get_redirect_url("http://www.example.com/about", Some(attributes)).is_none());
+    assert_eq!(viewed.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn get_redirect_url_tracks_control_without_redirecting() {
+    let sdk_key = "redirect_control_key";
+    let mock_server = mock_server(sdk_key).await;
+    let viewed = Arc::new(Mutex::new(Vec::new()));
+    let viewed_in_callback = viewed.clone();
+
+    let client = GrowthBookClientBuilder::new()
+        .api_url(mock_server.uri())
+        .client_key(sdk_key.to_string())
+        .on_experiment_viewed(Box::new(move |result| {
+            viewed_in_callback.lock().unwrap().push(result.variation_id);
+        }))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let attributes = GrowthBookAttribute::from(json!({ "id": "9" })).expect("Failed to create attributes");
+    assert!(client.get_redirect_url("http://www.example.com/home", Some(attributes)).is_none());
+    assert_eq!(*viewed.lock().unwrap(), vec![0]);
+}
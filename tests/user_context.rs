@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use growthbook_rust::client::{GrowthBookClient, GrowthBookClientBuilder, GrowthBookClientTrait};
+use growthbook_rust::model_public::{GrowthBookAttribute, GrowthBookAttributeValue, UserAttributes};
+use growthbook_rust::sticky_bucket::{AsyncStickyBucketService, InMemoryStickyBucketService, StickyBucketAdmin};
+use serde_json::json;
+
+fn attributes(value: serde_json::Value) -> Option<Vec<GrowthBookAttribute>> {
+    Some(GrowthBookAttribute::from(value).expect("Failed to create attributes"))
+}
+
+fn features() -> serde_json::Value {
+    json!({
+        "beta": {
+            "defaultValue": false,
+            "rules": [{ "condition": { "country": "BR" }, "force": true }]
+        },
+        "limit": { "defaultValue": 10 },
+        "title": { "defaultValue": "Hello" },
+        "experiment": {
+            "defaultValue": "default",
+            "rules": [{ "key": "my-test", "variations": ["control", "treatment"] }]
+        }
+    })
+}
+
+async fn client(views: Arc<Mutex<Vec<String>>>) -> GrowthBookClient {
+    GrowthBookClientBuilder::new()
+        .features_json(features())
+        .unwrap()
+        .attributes(HashMap::from([(
+            String::from("country"),
+            GrowthBookAttribute::new(String::from("country"), GrowthBookAttributeValue::String(String::from("US"))),
+        )]))
+        .on_experiment_viewed(Box::new(move |result| views.lock().unwrap().push(result.experiment_key)))
+        .build()
+        .await
+        .expect("Failed to build client")
+}
+
+#[tokio::test]
+async fn evaluates_like_the_client() {
+    let client = client(Arc::default()).await;
+    let user_attributes = attributes(json!({ "id": "1", "country": "BR" }));
+
+    let user = client.for_user(user_attributes.clone());
+
+    assert_eq!(user.is_on("beta"), client.is_on("beta", user_attributes.clone()));
+    assert_eq!(user.feature_result("experiment").value, client.feature_result("experiment", user_attributes.clone()).value);
+    assert_eq!(user.get_i64("limit", 0), 10);
+    assert_eq!(user.get_string("title", ""), "Hello");
+    // A mismatched type falls back to the default, as with the client.
+    assert!(user.get_bool("title", true));
+    let all = client.evaluate_all(user_attributes.clone());
+    for (feature_name, result) in user.evaluate_all() {
+        assert_eq!(result.value, all[&feature_name].value, "{feature_name}");
+    }
+    assert_eq!(user.explain_feature("beta").trace.len(), client.explain_feature("beta", user_attributes).trace.len());
+}
+
+#[tokio::test]
+async fn keeps_its_snapshot() {
+    let client = client(Arc::default()).await;
+    let user = client.for_user(attributes(json!({ "id": "1" })));
+
+    client.set_forced_features(HashMap::from([(String::from("title"), json!("Bye"))]));
+
+    assert_eq!(user.get_string("title", ""), "Hello");
+    assert_eq!(client.for_user(None).get_string("title", ""), "Bye");
+    assert!(!Arc::ptr_eq(user.snapshot(), &client.snapshot()));
+}
+
+#[tokio::test]
+async fn tracks_exposures_through_the_client() {
+    let views = Arc::new(Mutex::new(Vec::new()));
+    let client = client(views.clone()).await;
+
+    let user = client.for_user(attributes(json!({ "id": "1" })));
+    user.feature_result("experiment");
+    user.feature_result("experiment");
+
+    // Deduplicated by the client, across contexts too.
+    client.for_user(attributes(json!({ "id": "1" }))).feature_result("experiment");
+    assert_eq!(views.lock().unwrap().clone(), vec![String::from("my-test")]);
+}
+
+#[tokio::test]
+async fn moves_into_spawned_tasks() {
+    let views = Arc::new(Mutex::new(Vec::new()));
+    let client = client(views.clone()).await;
+    let user = client.for_user(attributes(json!({ "id": "1" })));
+    drop(client);
+
+    let title = tokio::spawn(async move {
+        user.feature_result("experiment");
+        user.get_string("title", "")
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(title, "Hello");
+    assert_eq!(views.lock().unwrap().clone(), vec![String::from("my-test")]);
+}
+
+#[tokio::test]
+async fn prefetches_sticky_buckets_once() {
+    let service = Arc::new(InMemoryStickyBucketService::new());
+    let client = GrowthBookClientBuilder::new()
+        .features_json(features())
+        .unwrap()
+        .async_sticky_bucket_service(service.clone() as Arc<dyn AsyncStickyBucketService>)
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let user = client.for_user_async(attributes(json!({ "id": "1" }))).await;
+    let value = user.feature_result("experiment").value;
+    assert_eq!(user.feature_result("experiment").value, value);
+    assert!(service.all_documents().unwrap().is_empty());
+
+    user.flush_sticky_buckets().await;
+
+    let documents = service.all_documents().unwrap();
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0].key(), "id||1");
+    assert!(documents[0].assignments.contains_key("my-test__0"));
+}
+
+#[test]
+fn indexed_attributes_resolve_to_the_first_entry_per_key() {
+    let country = |value: &str| GrowthBookAttribute::new(String::from("country"), GrowthBookAttributeValue::String(String::from(value)));
+    let attributes = UserAttributes::from(vec![country("US"), country("BR")]);
+
+    assert_eq!(attributes.get("country"), Some(&country("US")));
+    assert_eq!(attributes.get("id"), None);
+    assert_eq!(attributes.len(), 2);
+}